@@ -0,0 +1,75 @@
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+const DICT_DIR_ENV: &str = "VOICEVOX_EMBED_DICT_DIR";
+const MODEL_DIR_ENV: &str = "VOICEVOX_EMBED_MODEL_DIR";
+
+fn get_dir(name: &str) -> PathBuf {
+    let dir = std::env::var_os(name).unwrap_or_else(|| {
+        panic!("`embed`フィーチャーを使う場合は、環境変数`{name}`でディレクトリを指定してください")
+    });
+    let dir = Path::new(&dir)
+        .canonicalize()
+        .unwrap_or_else(|e| panic!("`{name}`のディレクトリが開けません：{e}"));
+    println!("cargo:rerun-if-changed={}", dir.display());
+    dir
+}
+
+/// ディレクトリの中のファイルを、名前の順に`(ファイル名, パス)`で返す。
+fn list_files(dir: &Path, extension: Option<&str>) -> Vec<(String, PathBuf)> {
+    let mut files: Vec<(String, PathBuf)> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            extension.is_none_or(|extension| {
+                path.extension().and_then(|e| e.to_str()) == Some(extension)
+            })
+        })
+        .map(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap().to_string();
+            (name, path)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn write_files(code: &mut String, doc: &str, name: &str, files: &[(String, PathBuf)]) {
+    writeln!(code, "/// {doc}").unwrap();
+    writeln!(code, "pub(crate) static {name}: &[(&str, &[u8])] = &[").unwrap();
+    for (file_name, path) in files {
+        writeln!(code, "    ({file_name:?}, include_bytes!({:?})),", path).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={DICT_DIR_ENV}");
+    println!("cargo:rerun-if-env-changed={MODEL_DIR_ENV}");
+    if std::env::var_os("CARGO_FEATURE_EMBED").is_none() {
+        return;
+    }
+
+    let dict_files = list_files(&get_dir(DICT_DIR_ENV), None);
+    let model_files = list_files(&get_dir(MODEL_DIR_ENV), Some("vvm"));
+
+    let mut code = String::new();
+    write_files(
+        &mut code,
+        "埋め込んだOpen JTalkの辞書のファイル。",
+        "DICT_FILES",
+        &dict_files,
+    );
+    write_files(
+        &mut code,
+        "埋め込んだ音声モデルのVVMファイル。",
+        "MODEL_FILES",
+        &model_files,
+    );
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("embedded.rs"), code).unwrap();
+}
@@ -0,0 +1,199 @@
+use crate::{
+    count_moras, validate_pronunciation, AccentPhrase, AudioQuery, StyleId, Synthesizer,
+    UserDictWordError, VoicevoxError, MORA_LIST,
+};
+use thiserror::Error;
+
+/// 試聴用の音声で単語の後ろに付ける助詞。平板型と尾高型は、後ろに助詞が付くと区別できる。
+const PREVIEW_PARTICLE: &str = "ガ";
+
+/// アクセント型の推定のエラー。
+#[derive(Error, Debug)]
+pub enum AccentSuggestionError {
+    /// 単語が不正
+    #[error("単語が不正：{0}")]
+    Word(#[from] UserDictWordError),
+
+    /// 音声合成に失敗した
+    #[error("音声合成に失敗した")]
+    Voicevox(#[from] VoicevoxError),
+}
+
+/// アクセント型の候補。
+#[derive(Debug, Clone)]
+pub struct AccentTypeCandidate {
+    /// アクセント型。
+    pub accent_type: usize,
+    /// 試聴用の、単語の後ろに助詞「が」を付けたAquesTalk風記法のカナ。
+    pub kana: String,
+    /// `kana`から生成したアクセント句。
+    pub accent_phrases: Vec<AccentPhrase>,
+    /// 試聴用のWAV形式の音声データ。
+    pub wav: Vec<u8>,
+}
+
+/// [`Synthesizer::suggest_accent_type`]の結果。
+#[derive(Debug, Clone)]
+pub struct AccentTypeSuggestion {
+    /// カタカナにした読み。
+    pub pronunciation: String,
+    /// コアが予測したアクセント型。予測した読みのモーラ数が合わない場合は`None`。
+    pub predicted: Option<usize>,
+    /// 最も可能性の高いアクセント型。予測できなかった場合は平板型（0）。
+    pub suggested: usize,
+    /// 0からモーラ数までのすべてのアクセント型の候補。アクセント型の順に並ぶ。
+    pub candidates: Vec<AccentTypeCandidate>,
+}
+
+impl AccentTypeSuggestion {
+    /// 最も可能性の高いアクセント型の候補。
+    pub fn suggested_candidate(&self) -> &AccentTypeCandidate {
+        &self.candidates[self.suggested]
+    }
+}
+
+impl Synthesizer {
+    /// ユーザー辞書に追加する単語のアクセント型を推定し、候補ごとに試聴用の音声を合成する。
+    ///
+    /// 表記、次に読みをそのまま解析したときのアクセントを予測として使う。
+    ///
+    /// # Arguments
+    /// * `surface` - 単語の表記。
+    /// * `pronunciation` - 単語の読み。ひらがなはカタカナにする。
+    /// * `style_id` - 試聴用の音声のスタイルID。
+    pub fn suggest_accent_type(
+        &self,
+        surface: &str,
+        pronunciation: &str,
+        style_id: StyleId,
+    ) -> std::result::Result<AccentTypeSuggestion, AccentSuggestionError> {
+        let pronunciation = validate_pronunciation(pronunciation)?;
+        let mora_count = count_moras(&pronunciation);
+
+        let mut predicted = None;
+        for text in [surface, &pronunciation] {
+            predicted = self.predict_accent_type(text, mora_count, style_id)?;
+            if predicted.is_some() {
+                break;
+            }
+        }
+
+        let moras = kana_moras(&pronunciation);
+        let mut template: Option<AudioQuery> = None;
+        let mut candidates = Vec::with_capacity(mora_count + 1);
+        for accent_type in 0..=mora_count {
+            let kana = preview_kana(&moras, accent_type);
+            let accent_phrases = self.create_accent_phrases_from_kana(&kana, style_id)?;
+            let template = match &template {
+                Some(template) => template,
+                None => template.insert(self.create_audio_query_from_kana(&kana, style_id)?),
+            };
+            let audio_query = AudioQuery {
+                accent_phrases: accent_phrases.clone(),
+                kana: Some(kana.clone()),
+                ..template.clone()
+            };
+            let wav = self.synthesis(&audio_query, style_id, Default::default())?;
+            candidates.push(AccentTypeCandidate {
+                accent_type,
+                kana,
+                accent_phrases,
+                wav,
+            });
+        }
+
+        Ok(AccentTypeSuggestion {
+            pronunciation,
+            predicted,
+            suggested: predicted.unwrap_or(0),
+            candidates,
+        })
+    }
+
+    /// `text`を解析したアクセントから、モーラ数が`mora_count`の単語のアクセント型を予測する。
+    pub(crate) fn predict_accent_type(
+        &self,
+        text: &str,
+        mora_count: usize,
+        style_id: StyleId,
+    ) -> crate::Result<Option<usize>> {
+        let accent_phrases = self.create_accent_phrases(text, style_id)?;
+        let [accent_phrase] = accent_phrases.as_slice() else {
+            return Ok(None);
+        };
+        if accent_phrase.moras.len() != mora_count {
+            return Ok(None);
+        }
+
+        // 単語だけでは平板型と尾高型を区別できないので、より多い平板型とみなす。
+        if accent_phrase.accent >= mora_count {
+            Ok(Some(0))
+        } else {
+            Ok(Some(accent_phrase.accent))
+        }
+    }
+}
+
+/// カタカナの読みを、AquesTalk風記法で使えるモーラに分ける。
+///
+/// 長音は前のモーラの母音にする。モーラとして解釈できない文字はそのまま残す。
+fn kana_moras(pronunciation: &str) -> Vec<String> {
+    let chars: Vec<char> = pronunciation.chars().collect();
+    let mut moras: Vec<(String, &str)> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == 'ー' {
+            let vowel = moras.last().map_or("", |(_, vowel)| *vowel);
+            let mora = match vowel {
+                "a" => "ア",
+                "i" => "イ",
+                "u" => "ウ",
+                "e" => "エ",
+                "o" => "オ",
+                "N" => "ン",
+                _ => "ー",
+            };
+            moras.push((mora.to_string(), vowel));
+            i += 1;
+            continue;
+        }
+
+        let found = (1..=(chars.len() - i).min(2)).rev().find_map(|len| {
+            let text: String = chars[i..i + len].iter().collect();
+            MORA_LIST
+                .iter()
+                .find(|(mora_text, _, _)| *mora_text == text)
+                .map(|(mora_text, _, vowel)| (mora_text.to_string(), *vowel, len))
+        });
+        match found {
+            Some((mora, vowel, len)) => {
+                moras.push((mora, vowel));
+                i += len;
+            }
+            None => {
+                moras.push((chars[i].to_string(), ""));
+                i += 1;
+            }
+        }
+    }
+
+    moras.into_iter().map(|(mora, _)| mora).collect()
+}
+
+/// 後ろに助詞を付けた、アクセント型が`accent_type`の試聴用のカナを作る。
+fn preview_kana(moras: &[String], accent_type: usize) -> String {
+    let mut kana = String::new();
+    for (i, mora) in moras.iter().enumerate() {
+        kana.push_str(mora);
+        if i + 1 == accent_type {
+            kana.push('\'');
+        }
+    }
+    kana.push_str(PREVIEW_PARTICLE);
+    // 平板型は助詞まで下がらない。
+    if accent_type == 0 {
+        kana.push('\'');
+    }
+
+    kana
+}
@@ -0,0 +1,79 @@
+use crate::VoicevoxError;
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// メモリ上のデータからの音声モデルや辞書の読み込みのエラー。
+#[derive(Error, Debug)]
+pub enum AssetLoadError {
+    /// データの読み込み、または一時ファイルへの書き出しに失敗した
+    #[error("データの読み込み、または一時ファイルへの書き出しに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// コアでの読み込みに失敗した
+    #[error("コアでの読み込みに失敗した")]
+    Voicevox(#[from] VoicevoxError),
+}
+
+/// C APIにパスで渡すために、一時ディレクトリに書き出したファイルやディレクトリ。
+///
+/// コアは読み込んだ後もパスを開きなおすことがあるので、使い終わるまで保持し、破棄するときに削除する。
+pub(crate) struct StagedPath {
+    path: PathBuf,
+}
+
+impl StagedPath {
+    fn new(name: &str) -> Self {
+        Self {
+            path: std::env::temp_dir().join(format!(
+                "voicevox_core_rs_{}_{}{}",
+                std::process::id(),
+                Uuid::new_v4(),
+                name
+            )),
+        }
+    }
+
+    /// `reader`の内容を一時ファイルに書き出す。
+    pub(crate) fn file(reader: &mut impl Read, extension: &str) -> std::io::Result<Self> {
+        let staged = Self::new(&format!(".{extension}"));
+        // 途中で失敗した場合も、`staged`を破棄するときに削除される。
+        let mut file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(&staged.path)?;
+        std::io::copy(reader, &mut file)?;
+        file.sync_all()?;
+
+        Ok(staged)
+    }
+
+    /// `(ファイル名, 内容)`の組を一時ディレクトリに書き出す。
+    pub(crate) fn dir(files: &[(&str, &[u8])]) -> std::io::Result<Self> {
+        let staged = Self::new("");
+        std::fs::create_dir(&staged.path)?;
+        for (name, content) in files {
+            std::fs::write(staged.path.join(name), content)?;
+        }
+
+        Ok(staged)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for StagedPath {
+    fn drop(&mut self) {
+        let _ = if self.path.is_dir() {
+            std::fs::remove_dir_all(&self.path)
+        } else {
+            std::fs::remove_file(&self.path)
+        };
+    }
+}
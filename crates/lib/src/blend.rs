@@ -0,0 +1,155 @@
+use crate::{AccentPhrase, Result, StyleId, SynthesisOptions, Synthesizer};
+
+/// 韻律のブレンドの重み。0.0で1つ目のスタイル、1.0で2つ目のスタイルとなる。
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlendWeight {
+    /// 全体で一定の重み。
+    Constant(f32),
+    /// 時間に沿って変化する重み。
+    ///
+    /// `(位置, 重み)`の組を位置の昇順で並べたもの。位置は発話全体の長さに対する割合（0.0〜1.0）で、
+    /// 間は線形補間する。
+    Curve(Vec<(f32, f32)>),
+}
+
+impl BlendWeight {
+    /// 位置（0.0〜1.0）での重みを返す。
+    pub fn at(&self, position: f32) -> f32 {
+        let weight = match self {
+            BlendWeight::Constant(weight) => *weight,
+            BlendWeight::Curve(points) => match points.iter().position(|(x, _)| *x >= position) {
+                None => points.last().map_or(0.0, |(_, weight)| *weight),
+                Some(0) => points[0].1,
+                Some(index) => {
+                    let (x0, y0) = points[index - 1];
+                    let (x1, y1) = points[index];
+                    y0 + (y1 - y0) * (position - x0) / (x1 - x0)
+                }
+            },
+        };
+
+        weight.clamp(0.0, 1.0)
+    }
+}
+
+/// 同じテキストの2つのAccentPhraseの配列の音高・音素長を、モーラごとに混ぜる。
+///
+/// 位置は`base`のモーラの中央の時刻で決める。音高が0（無声）のモーラは`base`の音高のままにする。
+///
+/// # Panics
+///
+/// `base`と`other`のアクセント句・モーラの数が異なる場合。
+pub fn blend_accent_phrases(
+    base: &[AccentPhrase],
+    other: &[AccentPhrase],
+    weight: &BlendWeight,
+) -> Vec<AccentPhrase> {
+    assert_eq!(base.len(), other.len(), "accent phrase count mismatch");
+
+    let total_length: f32 = base
+        .iter()
+        .flat_map(|accent_phrase| accent_phrase.moras.iter().chain(&accent_phrase.pause_mora))
+        .map(|mora| mora.consonant_length.unwrap_or(0.0) + mora.vowel_length)
+        .sum();
+    let mut time = 0.0;
+    let mut position = |length: f32| {
+        let center = time + length / 2.0;
+        time += length;
+        if total_length > 0.0 {
+            center / total_length
+        } else {
+            0.0
+        }
+    };
+    let lerp = |a: f32, b: f32, weight: f32| a + (b - a) * weight;
+
+    base.iter()
+        .zip(other)
+        .map(|(base_phrase, other_phrase)| {
+            assert_eq!(
+                base_phrase.moras.len(),
+                other_phrase.moras.len(),
+                "mora count mismatch"
+            );
+
+            let mut blended = base_phrase.clone();
+            for (mora, other_mora) in blended.moras.iter_mut().zip(&other_phrase.moras) {
+                let weight = weight.at(position(
+                    mora.consonant_length.unwrap_or(0.0) + mora.vowel_length,
+                ));
+                if mora.pitch > 0.0 && other_mora.pitch > 0.0 {
+                    mora.pitch = lerp(mora.pitch, other_mora.pitch, weight);
+                }
+                if let (Some(consonant_length), Some(other_length)) =
+                    (&mut mora.consonant_length, other_mora.consonant_length)
+                {
+                    *consonant_length = lerp(*consonant_length, other_length, weight);
+                }
+                mora.vowel_length = lerp(mora.vowel_length, other_mora.vowel_length, weight);
+            }
+            if let (Some(pause_mora), Some(other_pause)) =
+                (&mut blended.pause_mora, &other_phrase.pause_mora)
+            {
+                let weight = weight.at(position(pause_mora.vowel_length));
+                pause_mora.vowel_length =
+                    lerp(pause_mora.vowel_length, other_pause.vowel_length, weight);
+            }
+
+            blended
+        })
+        .collect()
+}
+
+impl Synthesizer {
+    /// AccentPhraseの配列の音高・音素長を、2つのスタイルで生成しなおして混ぜる。
+    ///
+    /// # Arguments
+    /// * `accent_phrases` - AccentPhraseの配列。
+    /// * `base_style_id` - 1つ目（重み0.0）のスタイルID。
+    /// * `other_style_id` - 2つ目（重み1.0）のスタイルID。
+    /// * `weight` - ブレンドの重み。
+    pub fn blend_mora_data(
+        &self,
+        accent_phrases: &[AccentPhrase],
+        base_style_id: StyleId,
+        other_style_id: StyleId,
+        weight: &BlendWeight,
+    ) -> Result<Vec<AccentPhrase>> {
+        let base = self.replace_mora_data(accent_phrases, base_style_id)?;
+        let other = self.replace_mora_data(accent_phrases, other_style_id)?;
+
+        Ok(blend_accent_phrases(&base, &other, weight))
+    }
+
+    /// 日本語テキストから、2つのスタイルの韻律を混ぜて音声を合成する。
+    ///
+    /// # Arguments
+    /// * `text` - 音声合成するテキスト。
+    /// * `base_style_id` - 1つ目（重み0.0）のスタイルID。
+    /// * `other_style_id` - 2つ目（重み1.0）のスタイルID。
+    /// * `weight` - ブレンドの重み。
+    /// * `target_style_id` - 音声を合成するスタイルID。
+    /// * `options` - 音声合成のオプション。
+    ///
+    /// # Returns
+    /// WAV形式の音声データ。
+    pub fn synthesis_blend(
+        &self,
+        text: &str,
+        base_style_id: StyleId,
+        other_style_id: StyleId,
+        weight: &BlendWeight,
+        target_style_id: StyleId,
+        options: SynthesisOptions,
+    ) -> Result<Vec<u8>> {
+        let mut audio_query = self.create_audio_query(text, base_style_id)?;
+        audio_query.accent_phrases = self.blend_mora_data(
+            &audio_query.accent_phrases,
+            base_style_id,
+            other_style_id,
+            weight,
+        )?;
+
+        self.synthesis(&audio_query, target_style_id, options)
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    count_moras, normalize::to_half_width, EnglishTransliterator, Result, StyleId, Synthesizer,
+    UserDictWord,
+};
+use indexmap::IndexMap;
+use regex::Regex;
+use std::{collections::HashMap, sync::LazyLock};
+
+/// 誤読の疑いの種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CorpusFindingKind {
+    /// 英単語をアルファベット1文字ずつ読んでいる。
+    SpelledOut,
+    /// 辞書に無い漢字の熟語を、1文字ずつの読みをつなげて読んでいる。
+    PerCharacterReading,
+    /// 文脈によって読みが変わる。
+    VaryingReading,
+}
+
+/// 誤読の疑いのある表記。
+#[derive(Debug, Clone)]
+pub struct CorpusFinding {
+    /// 表記。
+    pub surface: String,
+    /// 誤読の疑いの種類。
+    pub kinds: Vec<CorpusFindingKind>,
+    /// コーパス中に現れた回数。
+    pub count: usize,
+    /// 表記だけを解析したときの読み。
+    pub reading: String,
+    /// 文脈の中での読みと、その回数。多いものから順に並ぶ。
+    pub readings_in_context: Vec<(String, usize)>,
+    /// ユーザー辞書に追加する単語の候補。可能性の高いものから順に並ぶ。誤読している読みは含まず、
+    /// 正しい読みの手がかりが無い場合は空になる。
+    pub candidates: Vec<UserDictWord>,
+}
+
+/// [`Synthesizer::scan_corpus`]のオプション。
+#[derive(Debug, Clone)]
+pub struct CorpusScanOptions {
+    /// この回数以上現れた表記だけを報告する。
+    pub min_count: usize,
+    /// 文脈の中での読みを調べるときに、表記の後ろに付けて解析する最大の文字数。
+    pub context_length: usize,
+}
+
+impl Default for CorpusScanOptions {
+    fn default() -> Self {
+        Self {
+            min_count: 1,
+            context_length: 4,
+        }
+    }
+}
+
+/// 調べる表記。英字の並びと2文字以上の漢字の並び。
+static TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-zＡ-Ｚａ-ｚ]{2,}|\p{Han}{2,}").unwrap());
+
+#[derive(Default)]
+struct SurfaceStats {
+    count: usize,
+    readings_in_context: IndexMap<String, usize>,
+}
+
+/// 解析した読みを覚えておき、同じテキストを何度も解析しないようにする。
+struct Reader<'a> {
+    synthesizer: &'a Synthesizer,
+    style_id: StyleId,
+    cache: HashMap<String, String>,
+}
+
+impl Reader<'_> {
+    fn read(&mut self, text: &str) -> Result<String> {
+        if let Some(reading) = self.cache.get(text) {
+            return Ok(reading.clone());
+        }
+        let reading: String = self
+            .synthesizer
+            .create_accent_phrases(text, self.style_id)?
+            .iter()
+            .flat_map(|accent_phrase| &accent_phrase.moras)
+            .map(|mora| mora.text.as_str())
+            .collect();
+        self.cache.insert(text.to_string(), reading.clone());
+
+        Ok(reading)
+    }
+
+    /// 1文字ずつ解析した読みをつなげる。
+    fn read_per_character(&mut self, text: &str) -> Result<String> {
+        let mut reading = String::new();
+        for c in text.chars() {
+            reading.push_str(&self.read(&c.to_string())?);
+        }
+
+        Ok(reading)
+    }
+
+    /// `context`が後ろに続くときの`surface`の読み。`context`の読みが変わる場合は`None`。
+    fn read_in_context(&mut self, surface: &str, context: &str) -> Result<Option<String>> {
+        if context.is_empty() {
+            return self.read(surface).map(Some);
+        }
+        let whole = self.read(&format!("{surface}{context}"))?;
+        let context = self.read(context)?;
+
+        Ok(whole
+            .strip_suffix(context.as_str())
+            .filter(|reading| !reading.is_empty())
+            .map(str::to_string))
+    }
+}
+
+fn is_latin(surface: &str) -> bool {
+    surface.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+impl Synthesizer {
+    /// チャットのログなどのテキストを解析し、誤読の疑いのある表記とユーザー辞書の単語の候補を探す。
+    ///
+    /// 英字の並びと2文字以上の漢字の並びを調べ、次のものを報告する。
+    ///
+    /// - 英単語をアルファベット1文字ずつ読んでいるもの。大文字だけの略語は除く。
+    /// - 漢字の熟語を、1文字ずつ解析した読みをつなげて読んでいるもの。辞書に無い熟語は音読みなどの
+    ///   1文字ずつの読みになることが多い。
+    /// - 後ろに続く文字によって読みが変わるもの。
+    ///
+    /// 結果は現れた回数の多い順に並ぶ。
+    ///
+    /// # Arguments
+    /// * `lines` - 解析するテキストの行。
+    /// * `style_id` - 解析に使う音声のスタイルID。
+    /// * `options` - オプション。
+    pub fn scan_corpus<'a>(
+        &self,
+        lines: impl IntoIterator<Item = &'a str>,
+        style_id: StyleId,
+        options: &CorpusScanOptions,
+    ) -> Result<Vec<CorpusFinding>> {
+        let mut reader = Reader {
+            synthesizer: self,
+            style_id,
+            cache: HashMap::new(),
+        };
+
+        let mut stats: IndexMap<String, SurfaceStats> = IndexMap::new();
+        for line in lines {
+            for found in TOKEN.find_iter(line) {
+                let surface = to_half_width(found.as_str());
+                let context: String = line[found.end()..]
+                    .chars()
+                    .take_while(|c| !c.is_whitespace() && !c.is_ascii_punctuation())
+                    .take_while(|c| !matches!(c, '、' | '。' | '！' | '？' | '「' | '」'))
+                    .take(options.context_length)
+                    .collect();
+                let reading = reader.read_in_context(&surface, &context)?;
+
+                let stats = stats.entry(surface).or_default();
+                stats.count += 1;
+                if let Some(reading) = reading {
+                    *stats.readings_in_context.entry(reading).or_default() += 1;
+                }
+            }
+        }
+
+        let english = EnglishTransliterator::default();
+        let mut findings = vec![];
+        for (surface, stats) in stats {
+            if stats.count < options.min_count {
+                continue;
+            }
+            let reading = reader.read(&surface)?;
+            let mut readings_in_context: Vec<(String, usize)> =
+                stats.readings_in_context.into_iter().collect();
+            readings_in_context.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+            let mut kinds = vec![];
+            let mut pronunciations = vec![];
+            if is_latin(&surface) {
+                let is_acronym = surface.chars().all(|c| c.is_ascii_uppercase());
+                if !is_acronym && reading == reader.read_per_character(&surface)? {
+                    kinds.push(CorpusFindingKind::SpelledOut);
+                    pronunciations.push(english.transliterate(&surface));
+                }
+            } else if reading == reader.read_per_character(&surface)? {
+                kinds.push(CorpusFindingKind::PerCharacterReading);
+            }
+            if readings_in_context.len() > 1 {
+                kinds.push(CorpusFindingKind::VaryingReading);
+            }
+            if kinds.is_empty() {
+                continue;
+            }
+
+            // 1文字ずつ読んでいる読みは誤読なので候補にしない。
+            let is_misreading = kinds.iter().any(|kind| {
+                matches!(
+                    kind,
+                    CorpusFindingKind::SpelledOut | CorpusFindingKind::PerCharacterReading
+                )
+            });
+            for (reading_in_context, _) in &readings_in_context {
+                if is_misreading && *reading_in_context == reading {
+                    continue;
+                }
+                pronunciations.push(reading_in_context.clone());
+            }
+            let mut candidates: Vec<UserDictWord> = vec![];
+            for pronunciation in pronunciations {
+                if candidates
+                    .iter()
+                    .any(|word| word.pronunciation == pronunciation)
+                {
+                    continue;
+                }
+                let accent_type = self
+                    .predict_accent_type(&pronunciation, count_moras(&pronunciation), style_id)?
+                    .unwrap_or(0);
+                if let Ok(word) = UserDictWord::builder(&surface, &pronunciation)
+                    .accent_type(accent_type)
+                    .build()
+                {
+                    candidates.push(word);
+                }
+            }
+
+            findings.push(CorpusFinding {
+                surface,
+                kinds,
+                count: stats.count,
+                reading,
+                readings_in_context,
+                candidates,
+            });
+        }
+        findings.sort_by_key(|finding| std::cmp::Reverse(finding.count));
+
+        Ok(findings)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{AssetLoadError, OpenJtalkRc, VoiceModel};
+
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+impl OpenJtalkRc {
+    /// バイナリに埋め込んだ辞書からOpen JTalkを構築する。`embed`フィーチャーが必要。
+    pub fn from_embedded() -> std::result::Result<Self, AssetLoadError> {
+        Self::from_files(DICT_FILES)
+    }
+}
+
+impl VoiceModel {
+    /// バイナリに埋め込んだ音声モデルのファイル名。`embed`フィーチャーが必要。
+    pub fn embedded_names() -> impl Iterator<Item = &'static str> {
+        MODEL_FILES.iter().map(|(name, _)| *name)
+    }
+
+    /// バイナリに埋め込んだ音声モデルを、ファイル名の順にすべて読み込む。`embed`フィーチャーが必要。
+    pub fn from_embedded() -> std::result::Result<Vec<Self>, AssetLoadError> {
+        MODEL_FILES
+            .iter()
+            .map(|(_, bytes)| Self::from_bytes(bytes))
+            .collect()
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    count_moras, UserDict, UserDictFileError, UserDictWord, UserDictWordType, VoicevoxError,
+    MAX_USER_DICT_PRIORITY,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// ENGINE形式のユーザー辞書の読み書きのエラー。
+#[derive(Error, Debug)]
+pub enum EngineDictError {
+    /// ファイルの読み書きに失敗した
+    #[error("ファイルの読み書きに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// JSONの変換に失敗した
+    #[error("JSONの変換に失敗した")]
+    Json(#[from] serde_json::Error),
+
+    /// ユーザー辞書の操作に失敗した
+    #[error("ユーザー辞書の操作に失敗した")]
+    Voicevox(#[from] VoicevoxError),
+}
+
+/// 品詞ごとの、ENGINEが使う文脈IDと品詞の詳細、優先度に対応するコスト。
+struct PartOfSpeech {
+    word_type: UserDictWordType,
+    context_id: i32,
+    part_of_speech: [&'static str; 4],
+    /// 優先度10から0に対応するコスト。
+    cost_candidates: [i32; 11],
+}
+
+const PARTS_OF_SPEECH: [PartOfSpeech; 5] = [
+    PartOfSpeech {
+        word_type: UserDictWordType::ProperNoun,
+        context_id: 1348,
+        part_of_speech: ["名詞", "固有名詞", "一般", "*"],
+        cost_candidates: [
+            -988, 3488, 4768, 6048, 7328, 8609, 8734, 8859, 8984, 9110, 14176,
+        ],
+    },
+    PartOfSpeech {
+        word_type: UserDictWordType::CommonNoun,
+        context_id: 1345,
+        part_of_speech: ["名詞", "一般", "*", "*"],
+        cost_candidates: [
+            -4445, 49, 1473, 2897, 4321, 5746, 6554, 7362, 8170, 8978, 15001,
+        ],
+    },
+    PartOfSpeech {
+        word_type: UserDictWordType::Verb,
+        context_id: 642,
+        part_of_speech: ["動詞", "自立", "*", "*"],
+        cost_candidates: [
+            3100, 6160, 6360, 6561, 6761, 6962, 7414, 7866, 8318, 8771, 13433,
+        ],
+    },
+    PartOfSpeech {
+        word_type: UserDictWordType::Adjective,
+        context_id: 20,
+        part_of_speech: ["形容詞", "自立", "*", "*"],
+        cost_candidates: [
+            1527, 3266, 3561, 3857, 4153, 4449, 5149, 5849, 6549, 7250, 10001,
+        ],
+    },
+    PartOfSpeech {
+        word_type: UserDictWordType::Suffix,
+        context_id: 1358,
+        part_of_speech: ["名詞", "接尾", "一般", "*"],
+        cost_candidates: [
+            4399, 5373, 6041, 6705, 7369, 8033, 9398, 10763, 11198, 11848, 13120,
+        ],
+    },
+];
+
+impl PartOfSpeech {
+    fn of(word_type: UserDictWordType) -> &'static Self {
+        PARTS_OF_SPEECH
+            .iter()
+            .find(|part_of_speech| part_of_speech.word_type == word_type)
+            .unwrap()
+    }
+}
+
+/// VOICEVOX ENGINEの`user_dict.json`の単語。
+///
+/// ENGINEの保存形式（`cost`）とAPIの形式（`priority`）のどちらも読み書きでき、読み込んだ方の形式で書き出す。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EngineUserDictWord {
+    /// 表記。
+    pub surface: String,
+    /// 優先度。APIの形式の場合のみ。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    /// コスト。保存形式の場合のみ。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<i32>,
+    /// 文脈ID。
+    pub context_id: i32,
+    /// 品詞。
+    pub part_of_speech: String,
+    /// 品詞細分類1。
+    pub part_of_speech_detail_1: String,
+    /// 品詞細分類2。
+    pub part_of_speech_detail_2: String,
+    /// 品詞細分類3。
+    pub part_of_speech_detail_3: String,
+    /// 活用型。
+    pub inflectional_type: String,
+    /// 活用形。
+    pub inflectional_form: String,
+    /// 原形。
+    pub stem: String,
+    /// 読み。
+    pub yomi: String,
+    /// 発音。
+    pub pronunciation: String,
+    /// アクセント型。
+    pub accent_type: usize,
+    /// モーラ数。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mora_count: Option<usize>,
+    /// アクセント結合規則。
+    pub accent_associative_rule: String,
+}
+
+impl EngineUserDictWord {
+    /// 単語の種類。文脈IDから判断し、わからない場合は品詞から判断する。
+    pub fn word_type(&self) -> UserDictWordType {
+        if let Some(part_of_speech) = PARTS_OF_SPEECH
+            .iter()
+            .find(|part_of_speech| part_of_speech.context_id == self.context_id)
+        {
+            return part_of_speech.word_type;
+        }
+        match (
+            self.part_of_speech.as_str(),
+            self.part_of_speech_detail_1.as_str(),
+        ) {
+            ("名詞", "固有名詞") => UserDictWordType::ProperNoun,
+            ("名詞", "接尾") => UserDictWordType::Suffix,
+            ("動詞", _) => UserDictWordType::Verb,
+            ("形容詞", _) => UserDictWordType::Adjective,
+            _ => UserDictWordType::CommonNoun,
+        }
+    }
+
+    /// 優先度。保存形式の場合はコストに最も近い優先度にする。
+    pub fn priority(&self) -> u32 {
+        if let Some(priority) = self.priority {
+            return priority;
+        }
+        let Some(cost) = self.cost else {
+            return MAX_USER_DICT_PRIORITY / 2;
+        };
+        let candidates = PartOfSpeech::of(self.word_type()).cost_candidates;
+        let index = (0..candidates.len())
+            .min_by_key(|&index| (candidates[index] - cost).abs())
+            .unwrap();
+
+        MAX_USER_DICT_PRIORITY - index as u32
+    }
+
+    /// このクレートの単語に変換する。
+    pub fn to_user_dict_word(&self) -> UserDictWord {
+        UserDictWord {
+            surface: self.surface.clone(),
+            pronunciation: self.pronunciation.clone(),
+            accent_type: self.accent_type,
+            word_type: self.word_type(),
+            priority: self.priority(),
+        }
+    }
+
+    /// このクレートの単語から、ENGINEのAPIの形式の単語を作る。
+    pub fn from_user_dict_word(word: &UserDictWord) -> Self {
+        let part_of_speech = PartOfSpeech::of(word.word_type);
+        let [part_of_speech_name, detail_1, detail_2, detail_3] = part_of_speech.part_of_speech;
+
+        Self {
+            surface: word.surface.clone(),
+            priority: Some(word.priority),
+            cost: None,
+            context_id: part_of_speech.context_id,
+            part_of_speech: part_of_speech_name.to_string(),
+            part_of_speech_detail_1: detail_1.to_string(),
+            part_of_speech_detail_2: detail_2.to_string(),
+            part_of_speech_detail_3: detail_3.to_string(),
+            inflectional_type: "*".to_string(),
+            inflectional_form: "*".to_string(),
+            stem: "*".to_string(),
+            yomi: word.pronunciation.clone(),
+            pronunciation: word.pronunciation.clone(),
+            accent_type: word.accent_type,
+            mora_count: Some(count_moras(&word.pronunciation)),
+            accent_associative_rule: "*".to_string(),
+        }
+    }
+
+    /// このクレートの単語の内容で更新する。変更の無い項目は元の値のままにする。
+    fn update(&mut self, word: &UserDictWord) {
+        let word_type_changed = self.word_type() != word.word_type;
+        if word_type_changed {
+            let fresh = Self::from_user_dict_word(word);
+            self.context_id = fresh.context_id;
+            self.part_of_speech = fresh.part_of_speech;
+            self.part_of_speech_detail_1 = fresh.part_of_speech_detail_1;
+            self.part_of_speech_detail_2 = fresh.part_of_speech_detail_2;
+            self.part_of_speech_detail_3 = fresh.part_of_speech_detail_3;
+            self.inflectional_type = fresh.inflectional_type;
+            self.inflectional_form = fresh.inflectional_form;
+            self.stem = fresh.stem;
+        }
+
+        self.surface.clone_from(&word.surface);
+        if self.pronunciation != word.pronunciation {
+            self.yomi.clone_from(&word.pronunciation);
+            self.pronunciation.clone_from(&word.pronunciation);
+            if self.mora_count.is_some() {
+                self.mora_count = Some(count_moras(&word.pronunciation));
+            }
+        }
+        self.accent_type = word.accent_type;
+        if word_type_changed || self.priority() != word.priority {
+            match &mut self.cost {
+                Some(cost) => {
+                    let index = MAX_USER_DICT_PRIORITY - word.priority.min(MAX_USER_DICT_PRIORITY);
+                    *cost = PartOfSpeech::of(word.word_type).cost_candidates[index as usize];
+                }
+                None => self.priority = Some(word.priority),
+            }
+        }
+    }
+}
+
+/// VOICEVOX ENGINEの`user_dict.json`。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct EngineUserDict {
+    /// UUIDと単語の組。
+    pub words: IndexMap<Uuid, EngineUserDictWord>,
+}
+
+impl EngineUserDict {
+    /// JSONから読み込む。
+    pub fn from_json(json: &str) -> std::result::Result<Self, EngineDictError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// JSONに変換する。
+    pub fn to_json(&self) -> std::result::Result<String, EngineDictError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// ファイルから読み込む。
+    pub fn load<S: AsRef<Path>>(path: S) -> std::result::Result<Self, EngineDictError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// ファイルに保存する。
+    pub fn save<S: AsRef<Path>>(&self, path: S) -> std::result::Result<(), EngineDictError> {
+        std::fs::write(path, self.to_json()?)?;
+
+        Ok(())
+    }
+
+    /// ユーザー辞書から作る。単語はENGINEのAPIの形式になる。
+    pub fn from_user_dict(user_dict: &UserDict) -> std::result::Result<Self, EngineDictError> {
+        Ok(Self {
+            words: user_dict
+                .iter()?
+                .map(|(uuid, word)| (uuid, EngineUserDictWord::from_user_dict_word(&word)))
+                .collect(),
+        })
+    }
+
+    /// ユーザー辞書の内容に合わせる。
+    ///
+    /// UUIDが同じ単語はENGINEにしかない項目を残したまま更新し、ユーザー辞書に無い単語は削除する。
+    /// そのため、読み込んだENGINEの辞書を[`EngineUserDict::to_user_dict`]で変換して編集し、この関数で
+    /// 書き戻すと、編集していない単語は元のまま残る。
+    pub fn update_from(
+        &mut self,
+        user_dict: &UserDict,
+    ) -> std::result::Result<(), EngineDictError> {
+        let mut words = IndexMap::new();
+        for (uuid, word) in user_dict.iter()? {
+            let engine_word = match self.words.swap_remove(&uuid) {
+                Some(mut engine_word) => {
+                    engine_word.update(&word);
+                    engine_word
+                }
+                None => EngineUserDictWord::from_user_dict_word(&word),
+            };
+            words.insert(uuid, engine_word);
+        }
+        self.words = words;
+
+        Ok(())
+    }
+
+    /// ユーザー辞書に変換する。UUIDはそのまま引き継ぐ。
+    pub fn to_user_dict(&self) -> std::result::Result<UserDict, EngineDictError> {
+        let words: IndexMap<&Uuid, UserDictWord> = self
+            .words
+            .iter()
+            .map(|(uuid, word)| (uuid, word.to_user_dict_word()))
+            .collect();
+
+        UserDict::from_words(words.iter().map(|(uuid, word)| (*uuid, word))).map_err(
+            |err| match err {
+                UserDictFileError::Io(err) => EngineDictError::Io(err),
+                UserDictFileError::Voicevox(err) => EngineDictError::Voicevox(err),
+                UserDictFileError::Encoding => unreachable!("文字コードの変換はしない"),
+            },
+        )
+    }
+}
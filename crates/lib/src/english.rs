@@ -0,0 +1,512 @@
+use crate::{spell_out, UserDictWord};
+use std::collections::{HashMap, HashSet};
+
+/// 同梱の英単語とカタカナ読みの辞書。
+const BUNDLED_DICTIONARY: &str = include_str!("../resources/english_katakana.tsv");
+
+/// 英単語をカタカナ読みにする変換器。
+///
+/// 辞書に載っている単語は辞書の読みを使い、載っていない単語は綴りの規則から読みを推定する。
+#[derive(Debug, Clone)]
+pub struct EnglishTransliterator {
+    dictionary: HashMap<String, String>,
+}
+
+impl Default for EnglishTransliterator {
+    /// 同梱の辞書を読み込んだ変換器を作成する。
+    fn default() -> Self {
+        let mut transliterator = Self::empty();
+        transliterator.load_tsv(BUNDLED_DICTIONARY);
+
+        transliterator
+    }
+}
+
+impl EnglishTransliterator {
+    /// 辞書が空の変換器を作成する。
+    pub fn empty() -> Self {
+        Self {
+            dictionary: HashMap::new(),
+        }
+    }
+
+    /// 辞書の単語数。
+    pub fn len(&self) -> usize {
+        self.dictionary.len()
+    }
+
+    /// 辞書が空かどうか。
+    pub fn is_empty(&self) -> bool {
+        self.dictionary.is_empty()
+    }
+
+    /// 辞書に単語を追加する。既にある場合は上書きする。
+    pub fn insert(&mut self, word: &str, reading: &str) {
+        self.dictionary
+            .insert(word.to_ascii_lowercase(), reading.to_string());
+    }
+
+    /// `単語<TAB>読み`の形式の行を辞書に追加する。`#`から始まる行と空行は無視する。
+    ///
+    /// # Returns
+    ///
+    /// 追加した単語の数。
+    pub fn load_tsv(&mut self, tsv: &str) -> usize {
+        let mut count = 0;
+        for line in tsv.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((word, reading)) = line.split_once('\t') {
+                self.insert(word.trim(), reading.trim());
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// 辞書から単語の読みを引く。大文字と小文字は区別しない。
+    pub fn lookup(&self, word: &str) -> Option<&str> {
+        self.dictionary
+            .get(&word.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// 英単語をカタカナ読みにする。
+    ///
+    /// 辞書に載っていない場合は、語尾の`s`・`ing`を除いた形を辞書から引き、それも無ければ綴りから推定する。
+    /// 1文字の単語はアルファベットの読みにする。
+    pub fn transliterate(&self, word: &str) -> String {
+        if let Some(reading) = self.lookup(word) {
+            return reading.to_string();
+        }
+        if word.chars().count() == 1 {
+            return spell_out(word);
+        }
+
+        let lower = word.to_ascii_lowercase();
+        if let Some(reading) = lower.strip_suffix("ing").and_then(|stem| self.lookup(stem)) {
+            return format!("{}イング", reading);
+        }
+        if let Some(reading) = lower
+            .strip_suffix("es")
+            .filter(|stem| stem.ends_with(['s', 'x']) || stem.ends_with("sh"))
+            .and_then(|stem| self.lookup(stem))
+        {
+            return format!("{}ズ", reading);
+        }
+        if let Some(reading) = lower.strip_suffix('s').and_then(|stem| self.lookup(stem)) {
+            return format!("{}ズ", reading);
+        }
+
+        transliterate_by_rule(&lower)
+    }
+
+    /// テキスト中の英単語をカタカナ読みにする。
+    ///
+    /// 2文字以上の大文字だけの単語は略語とみなし、辞書に載っていても変換しない。
+    pub fn transliterate_text(&self, text: &str) -> String {
+        replace_words(text, |word| {
+            if is_acronym(word) {
+                None
+            } else {
+                Some(self.transliterate(word))
+            }
+        })
+    }
+
+    /// 英単語をユーザー辞書の単語にする。読みが作れない場合（`hh`など）は`None`。
+    pub fn user_dict_word(&self, word: &str) -> Option<UserDictWord> {
+        let pronunciation = self.transliterate(word);
+        if pronunciation.is_empty() {
+            return None;
+        }
+
+        Some(UserDictWord::new(word, &pronunciation))
+    }
+
+    /// テキスト中の英単語（略語と1文字の単語を除く）をユーザー辞書の単語にする。
+    ///
+    /// 大文字と小文字を区別せずに重複を除き、最初に現れた表記を使う。読みが作れない単語は除く。
+    pub fn user_dict_words(&self, text: &str) -> Vec<UserDictWord> {
+        let mut seen = HashSet::new();
+        let mut words = vec![];
+        replace_words(text, |word| {
+            if word.len() >= 2 && !is_acronym(word) && seen.insert(word.to_ascii_lowercase()) {
+                words.extend(self.user_dict_word(word));
+            }
+            None
+        });
+
+        words
+    }
+}
+
+fn is_acronym(word: &str) -> bool {
+    word.len() >= 2 && word.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// テキスト中のアルファベットの並びを`f`の結果で置き換える。`None`の場合はそのままにする。
+fn replace_words(text: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphabetic()) {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let word = &rest[..end];
+        match f(word) {
+            Some(reading) => result.push_str(&reading),
+            None => result.push_str(word),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// 綴りを分解した単位。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    /// 子音。`bool`は促音を前に置くかどうか。
+    Consonant(&'static str, bool),
+    /// 母音。
+    Vowel(&'static str),
+}
+
+const SHORT_VOWELS: &[&str] = &["a", "i", "u", "e", "o"];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+/// 綴りの規則から英単語の読みを推定する。`word`は小文字のアルファベットのみからなる。
+fn transliterate_by_rule(word: &str) -> String {
+    render(&split_units(word))
+}
+
+fn split_units(word: &str) -> Vec<Unit> {
+    let chars: Vec<char> = word.chars().collect();
+    let len = chars.len();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+    let starts_with = |i: usize, pattern: &str| {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| at(i + offset) == c)
+    };
+    let has_vowel_before = |i: usize| chars[..i].iter().any(|&c| is_vowel(c) || c == 'y');
+    let is_consonant = |c: char| c.is_ascii_lowercase() && !is_vowel(c);
+
+    let mut units = vec![];
+    let mut i = 0;
+    while i < len {
+        let c = chars[i];
+
+        // 母音
+        if is_vowel(c) || (c == 'y' && i > 0 && is_consonant(at(i - 1))) {
+            // 語末のe
+            if c == 'e' && i == len - 1 && has_vowel_before(i) {
+                break;
+            }
+            if c == 'y' {
+                let vowel = if i == len - 1 && has_vowel_before(i) {
+                    "ii"
+                } else if i == len - 1 {
+                    "ai"
+                } else {
+                    "i"
+                };
+                units.push(Unit::Vowel(vowel));
+                i += 1;
+                continue;
+            }
+
+            let patterns: &[(&str, &str)] = &[
+                ("ook", "u"),
+                ("eigh", "ei"),
+                ("igh", "ai"),
+                ("air", "ea"),
+                ("ear", "ia"),
+                ("ee", "ii"),
+                ("ea", "ii"),
+                ("oo", "uu"),
+                ("ou", "au"),
+                ("ai", "ei"),
+                ("ay", "ei"),
+                ("oi", "oi"),
+                ("oy", "oi"),
+                ("au", "oo"),
+                ("aw", "oo"),
+                ("ew", "yuu"),
+                ("ey", "ii"),
+                ("ue", "uu"),
+            ];
+            if let Some((pattern, vowel)) = patterns.iter().find(|(p, _)| starts_with(i, p)) {
+                units.push(Unit::Vowel(vowel));
+                // `ook`の`k`は子音として残す。
+                i += pattern.trim_end_matches('k').len();
+                continue;
+            }
+            if starts_with(i, "ow") {
+                units.push(Unit::Vowel(if i + 2 == len { "ou" } else { "au" }));
+                i += 2;
+                continue;
+            }
+            if starts_with(i, "ie") {
+                units.push(Unit::Vowel(if i + 2 == len { "ai" } else { "ii" }));
+                i += 2;
+                continue;
+            }
+
+            // rの前の母音は長音にする。
+            if at(i + 1) == 'r' && !is_vowel(at(i + 2)) && at(i + 2) != 'r' {
+                units.push(Unit::Vowel(if c == 'o' { "oo" } else { "aa" }));
+                i += 2;
+                continue;
+            }
+
+            // 子音1つと語末のeが続く場合は長母音にする。
+            let magic_e = is_consonant(at(i + 1))
+                && at(i + 1) != 'x'
+                && at(i + 2) == 'e'
+                && (i + 3 == len || (i + 4 == len && at(i + 3) == 's'));
+            // 最初の開音節も長母音にする。
+            let open = is_consonant(at(i + 1))
+                && !matches!(at(i + 1), 'x' | 'r')
+                && (is_vowel(at(i + 2))
+                    && (at(i + 2) != 'e' || is_consonant(at(i + 3)) && i + 4 == len)
+                    || at(i + 2) == 'y')
+                && !has_vowel_before(i)
+                && c != 'e';
+            let vowel = if magic_e || open {
+                match c {
+                    'a' => "ee",
+                    'i' => "ai",
+                    'o' => "oo",
+                    'u' => "yuu",
+                    _ => "ii",
+                }
+            } else if c == 'u' && is_consonant(at(i + 1)) && !is_vowel(at(i + 2)) {
+                "a"
+            } else {
+                SHORT_VOWELS
+                    .iter()
+                    .find(|v| v.starts_with(c))
+                    .copied()
+                    .unwrap()
+            };
+            units.push(Unit::Vowel(vowel));
+            i += 1;
+            continue;
+        }
+
+        // 子音
+        if starts_with(i, "tion") || starts_with(i, "sion") {
+            let consonant = if c == 't' || i == 0 || !is_vowel(at(i - 1)) {
+                "sh"
+            } else {
+                "j"
+            };
+            units.extend([
+                Unit::Consonant(consonant, false),
+                Unit::Vowel("o"),
+                Unit::Consonant("n", false),
+            ]);
+            i += 4;
+            continue;
+        }
+        let (consonant, length) = match c {
+            _ if starts_with(i, "tch") => ("ch", 3),
+            _ if starts_with(i, "sch") => ("sk", 3),
+            _ if starts_with(i, "dg") => ("j", 2),
+            _ if starts_with(i, "ch") => ("ch", 2),
+            _ if starts_with(i, "sh") => ("sh", 2),
+            _ if starts_with(i, "th") => ("th", 2),
+            _ if starts_with(i, "ph") => ("f", 2),
+            _ if starts_with(i, "wh") => ("w", 2),
+            _ if starts_with(i, "ck") => ("k", 2),
+            _ if starts_with(i, "ng") && !is_vowel(at(i + 2)) => ("ng", 2),
+            _ if starts_with(i, "qu") => ("kw", 2),
+            _ if i == 0 && starts_with(i, "kn") => ("n", 2),
+            _ if i == 0 && starts_with(i, "wr") => ("r", 2),
+            _ if starts_with(i, "gh") => ("", 2),
+            'c' if matches!(at(i + 1), 'e' | 'i' | 'y') => ("s", 1),
+            'g' if matches!(at(i + 1), 'e' | 'i' | 'y')
+                && !(starts_with(i + 1, "er") && i + 3 == len) =>
+            {
+                ("j", 1)
+            }
+            's' if i > 0 && is_vowel(at(i - 1)) && is_vowel(at(i + 1)) => ("z", 1),
+            'c' | 'k' | 'q' => ("k", 1),
+            'x' => ("ks", 1),
+            'b' => ("b", 1),
+            'd' => ("d", 1),
+            'f' => ("f", 1),
+            'g' => ("g", 1),
+            'h' => ("h", 1),
+            'j' => ("j", 1),
+            'l' => ("l", 1),
+            'm' => ("m", 1),
+            'n' => ("n", 1),
+            'p' => ("p", 1),
+            'r' => ("r", 1),
+            's' => ("s", 1),
+            't' => ("t", 1),
+            'v' => ("v", 1),
+            'w' => ("w", 1),
+            'y' => ("y", 1),
+            'z' => ("z", 1),
+            _ => ("", 1),
+        };
+        if consonant.is_empty() {
+            i += length;
+            continue;
+        }
+
+        let after_short_vowel = matches!(
+            units.last(),
+            Some(Unit::Vowel(vowel)) if SHORT_VOWELS.contains(vowel)
+        );
+        // 同じ子音が2つ続く場合は1つにまとめる。
+        let doubled = length == 1 && at(i + 1) == c;
+        let length = if doubled { 2 } else { length };
+        let at_end = i + length == len || (i + length + 1 == len && at(i + length) == 'e');
+        let geminate = after_short_vowel
+            && (doubled
+                || at_end
+                || consonant == "ks"
+                || starts_with(i, "tch")
+                || starts_with(i, "dg"))
+            && matches!(
+                consonant,
+                "k" | "t" | "p" | "ch" | "d" | "g" | "f" | "sh" | "j" | "ks" | "b"
+            );
+        units.push(Unit::Consonant(consonant, geminate));
+        i += length;
+    }
+
+    units
+}
+
+/// 子音と母音（ア・イ・ウ・エ・オ）からカナを作る。
+fn kana(consonant: &str, vowel: usize) -> &'static str {
+    let row: [&str; 5] = match consonant {
+        "" => ["ア", "イ", "ウ", "エ", "オ"],
+        "k" => ["カ", "キ", "ク", "ケ", "コ"],
+        "g" => ["ガ", "ギ", "グ", "ゲ", "ゴ"],
+        "s" | "th" => ["サ", "シ", "ス", "セ", "ソ"],
+        "z" => ["ザ", "ジ", "ズ", "ゼ", "ゾ"],
+        "t" => ["タ", "ティ", "トゥ", "テ", "ト"],
+        "d" => ["ダ", "ディ", "ドゥ", "デ", "ド"],
+        "n" => ["ナ", "ニ", "ヌ", "ネ", "ノ"],
+        "h" => ["ハ", "ヒ", "フ", "ヘ", "ホ"],
+        "b" | "v" => ["バ", "ビ", "ブ", "ベ", "ボ"],
+        "p" => ["パ", "ピ", "プ", "ペ", "ポ"],
+        "m" => ["マ", "ミ", "ム", "メ", "モ"],
+        "y" => ["ヤ", "イ", "ユ", "イェ", "ヨ"],
+        "r" | "l" => ["ラ", "リ", "ル", "レ", "ロ"],
+        "w" => ["ワ", "ウィ", "ウ", "ウェ", "ウォ"],
+        "f" => ["ファ", "フィ", "フ", "フェ", "フォ"],
+        "sh" => ["シャ", "シ", "シュ", "シェ", "ショ"],
+        "ch" => ["チャ", "チ", "チュ", "チェ", "チョ"],
+        "j" => ["ジャ", "ジ", "ジュ", "ジェ", "ジョ"],
+        _ => unreachable!(),
+    };
+
+    row[vowel]
+}
+
+/// 母音の後に続けない子音の読み。
+fn standalone(consonant: &str) -> &'static str {
+    match consonant {
+        "t" => "ト",
+        "d" => "ド",
+        "ch" => "チ",
+        "j" => "ジ",
+        "n" => "ン",
+        "ng" => "ング",
+        "ks" => "クス",
+        "kw" => "ク",
+        "sk" => "スク",
+        "h" => "",
+        "w" => "ウ",
+        "y" => "イ",
+        consonant => kana(consonant, 2),
+    }
+}
+
+fn render(units: &[Unit]) -> String {
+    let mut reading = String::new();
+    let mut i = 0;
+    while i < units.len() {
+        match units[i] {
+            Unit::Consonant(consonant, geminate) => {
+                if geminate {
+                    reading.push('ッ');
+                }
+                match units.get(i + 1) {
+                    Some(Unit::Vowel(vowel)) => {
+                        let (prefix, consonant) = match consonant {
+                            "ng" => ("ン", "g"),
+                            "ks" => ("ク", "s"),
+                            "sk" => ("ス", "k"),
+                            consonant => ("", consonant),
+                        };
+                        reading.push_str(prefix);
+                        if consonant == "kw" {
+                            reading.push('ク');
+                            reading.push_str(&render_vowel("", vowel));
+                        } else {
+                            reading.push_str(&render_vowel(consonant, vowel));
+                        }
+                        i += 2;
+                        continue;
+                    }
+                    Some(Unit::Consonant("p" | "b", _)) if consonant == "m" => reading.push('ン'),
+                    _ => reading.push_str(standalone(consonant)),
+                }
+            }
+            Unit::Vowel(vowel) => reading.push_str(&render_vowel("", vowel)),
+        }
+        i += 1;
+    }
+
+    reading
+}
+
+fn render_vowel(consonant: &str, vowel: &str) -> String {
+    let index = |c: char| "aiueo".find(c).unwrap();
+    match vowel {
+        "yuu" if matches!(consonant, "" | "y") => "ユー".to_string(),
+        "yuu"
+            if matches!(
+                consonant,
+                "k" | "g" | "m" | "p" | "b" | "v" | "f" | "n" | "h"
+            ) =>
+        {
+            format!("{}ュー", kana(consonant, 1))
+        }
+        "yuu" => format!("{}ー", kana(consonant, 2)),
+        "ii" | "uu" | "oo" | "aa" | "ee" => {
+            let first = vowel.chars().next().unwrap();
+            let first = if vowel == "aa" { 'a' } else { first };
+            format!("{}ー", kana(consonant, index(first)))
+        }
+        "ou" => format!("{}ー", kana(consonant, index('o'))),
+        "ei" => format!("{}イ", kana(consonant, index('e'))),
+        "ai" | "oi" => format!(
+            "{}イ",
+            kana(consonant, index(vowel.chars().next().unwrap()))
+        ),
+        "au" => format!("{}ウ", kana(consonant, index('a'))),
+        "ea" => format!("{}ア", kana(consonant, index('e'))),
+        "ia" => format!("{}アー", kana(consonant, index('i'))),
+        vowel => kana(consonant, index(vowel.chars().next().unwrap())).to_string(),
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{AccentPhrase, MoraModel};
+use thiserror::Error;
+
+const UNVOICE_SYMBOL: char = '_';
+const ACCENT_SYMBOL: char = '\'';
+const NOPAUSE_DELIMITER: char = '/';
+const PAUSE_DELIMITER: char = '、';
+const WIDE_INTERROGATION_MARK: char = '？';
+
+/// カナとモーラの対応表。`(カナ, 子音, 母音)`の組で、子音が無い場合は空文字列。
+pub const MORA_LIST: &[(&str, &str, &str)] = &[
+    ("ヴォ", "v", "o"),
+    ("ヴェ", "v", "e"),
+    ("ヴィ", "v", "i"),
+    ("ヴァ", "v", "a"),
+    ("ヴ", "v", "u"),
+    ("ン", "", "N"),
+    ("ワ", "w", "a"),
+    ("ロ", "r", "o"),
+    ("レ", "r", "e"),
+    ("ル", "r", "u"),
+    ("リョ", "ry", "o"),
+    ("リュ", "ry", "u"),
+    ("リャ", "ry", "a"),
+    ("リェ", "ry", "e"),
+    ("リ", "r", "i"),
+    ("ラ", "r", "a"),
+    ("ヨ", "y", "o"),
+    ("ユ", "y", "u"),
+    ("ヤ", "y", "a"),
+    ("モ", "m", "o"),
+    ("メ", "m", "e"),
+    ("ム", "m", "u"),
+    ("ミョ", "my", "o"),
+    ("ミュ", "my", "u"),
+    ("ミャ", "my", "a"),
+    ("ミェ", "my", "e"),
+    ("ミ", "m", "i"),
+    ("マ", "m", "a"),
+    ("ポ", "p", "o"),
+    ("ボ", "b", "o"),
+    ("ホ", "h", "o"),
+    ("ペ", "p", "e"),
+    ("ベ", "b", "e"),
+    ("ヘ", "h", "e"),
+    ("プ", "p", "u"),
+    ("ブ", "b", "u"),
+    ("フォ", "f", "o"),
+    ("フェ", "f", "e"),
+    ("フィ", "f", "i"),
+    ("ファ", "f", "a"),
+    ("フ", "f", "u"),
+    ("ピョ", "py", "o"),
+    ("ピュ", "py", "u"),
+    ("ピャ", "py", "a"),
+    ("ピェ", "py", "e"),
+    ("ピ", "p", "i"),
+    ("ビョ", "by", "o"),
+    ("ビュ", "by", "u"),
+    ("ビャ", "by", "a"),
+    ("ビェ", "by", "e"),
+    ("ビ", "b", "i"),
+    ("ヒョ", "hy", "o"),
+    ("ヒュ", "hy", "u"),
+    ("ヒャ", "hy", "a"),
+    ("ヒェ", "hy", "e"),
+    ("ヒ", "h", "i"),
+    ("パ", "p", "a"),
+    ("バ", "b", "a"),
+    ("ハ", "h", "a"),
+    ("ノ", "n", "o"),
+    ("ネ", "n", "e"),
+    ("ヌ", "n", "u"),
+    ("ニョ", "ny", "o"),
+    ("ニュ", "ny", "u"),
+    ("ニャ", "ny", "a"),
+    ("ニェ", "ny", "e"),
+    ("ニ", "n", "i"),
+    ("ナ", "n", "a"),
+    ("ドゥ", "d", "u"),
+    ("ド", "d", "o"),
+    ("トゥ", "t", "u"),
+    ("ト", "t", "o"),
+    ("デョ", "dy", "o"),
+    ("デュ", "dy", "u"),
+    ("デャ", "dy", "a"),
+    ("ディ", "d", "i"),
+    ("デ", "d", "e"),
+    ("テョ", "ty", "o"),
+    ("テュ", "ty", "u"),
+    ("テャ", "ty", "a"),
+    ("ティ", "t", "i"),
+    ("テ", "t", "e"),
+    ("ツォ", "ts", "o"),
+    ("ツェ", "ts", "e"),
+    ("ツィ", "ts", "i"),
+    ("ツァ", "ts", "a"),
+    ("ツ", "ts", "u"),
+    ("ッ", "", "cl"),
+    ("チョ", "ch", "o"),
+    ("チュ", "ch", "u"),
+    ("チャ", "ch", "a"),
+    ("チェ", "ch", "e"),
+    ("チ", "ch", "i"),
+    ("ダ", "d", "a"),
+    ("タ", "t", "a"),
+    ("ゾ", "z", "o"),
+    ("ソ", "s", "o"),
+    ("ゼ", "z", "e"),
+    ("セ", "s", "e"),
+    ("ズィ", "z", "i"),
+    ("ズ", "z", "u"),
+    ("スィ", "s", "i"),
+    ("ス", "s", "u"),
+    ("ジョ", "j", "o"),
+    ("ジュ", "j", "u"),
+    ("ジャ", "j", "a"),
+    ("ジェ", "j", "e"),
+    ("ジ", "j", "i"),
+    ("ショ", "sh", "o"),
+    ("シュ", "sh", "u"),
+    ("シャ", "sh", "a"),
+    ("シェ", "sh", "e"),
+    ("シ", "sh", "i"),
+    ("ザ", "z", "a"),
+    ("サ", "s", "a"),
+    ("ゴ", "g", "o"),
+    ("コ", "k", "o"),
+    ("ゲ", "g", "e"),
+    ("ケ", "k", "e"),
+    ("グヮ", "gw", "a"),
+    ("グ", "g", "u"),
+    ("クヮ", "kw", "a"),
+    ("ク", "k", "u"),
+    ("ギョ", "gy", "o"),
+    ("ギュ", "gy", "u"),
+    ("ギャ", "gy", "a"),
+    ("ギェ", "gy", "e"),
+    ("ギ", "g", "i"),
+    ("キョ", "ky", "o"),
+    ("キュ", "ky", "u"),
+    ("キャ", "ky", "a"),
+    ("キェ", "ky", "e"),
+    ("キ", "k", "i"),
+    ("ガ", "g", "a"),
+    ("カ", "k", "a"),
+    ("オ", "", "o"),
+    ("エ", "", "e"),
+    ("ウォ", "w", "o"),
+    ("ウェ", "w", "e"),
+    ("ウィ", "w", "i"),
+    ("ウ", "", "u"),
+    ("イェ", "y", "e"),
+    ("イ", "", "i"),
+    ("ア", "", "a"),
+    ("ヴョ", "by", "o"),
+    ("ヴュ", "by", "u"),
+    ("ヴャ", "by", "a"),
+    ("ヲ", "", "o"),
+    ("ヱ", "", "e"),
+    ("ヰ", "", "i"),
+    ("ヮ", "w", "a"),
+    ("ョ", "y", "o"),
+    ("ュ", "y", "u"),
+    ("ヅ", "z", "u"),
+    ("ヂ", "j", "i"),
+    ("ヶ", "k", "e"),
+    ("ャ", "y", "a"),
+    ("ォ", "", "o"),
+    ("ェ", "", "e"),
+    ("ゥ", "", "u"),
+    ("ィ", "", "i"),
+    ("ァ", "", "a"),
+];
+
+/// AquesTalk風記法の解析エラーの種類。
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KanaParseErrorKind {
+    /// モーラとして解釈できない文字。
+    #[error("`{0}`はモーラとして解釈できない")]
+    UnknownText(String),
+
+    /// アクセント句が空。
+    #[error("アクセント句が空")]
+    EmptyAccentPhrase,
+
+    /// アクセント句にアクセントが無い。
+    #[error("アクセント句にアクセント`{ACCENT_SYMBOL}`が無い")]
+    MissingAccent,
+
+    /// アクセントが2つ以上ある。
+    #[error("アクセント`{ACCENT_SYMBOL}`が2つ以上ある")]
+    DuplicateAccent,
+
+    /// アクセントがアクセント句の先頭にある。
+    #[error("アクセント`{ACCENT_SYMBOL}`がアクセント句の先頭にある")]
+    AccentAtStart,
+
+    /// 疑問符がアクセント句の末尾以外にある。
+    #[error("疑問符`{WIDE_INTERROGATION_MARK}`がアクセント句の末尾以外にある")]
+    InterrogativeNotAtEnd,
+
+    /// 無声化記号の後に無声化できるモーラが無い。
+    #[error("無声化記号`{UNVOICE_SYMBOL}`の後に無声化できるモーラが無い")]
+    InvalidUnvoice,
+}
+
+/// AquesTalk風記法の解析エラー。
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{position}文字目: {kind}")]
+pub struct KanaParseError {
+    /// エラーが起きた位置。テキスト先頭からの文字数（0始まり）。
+    pub position: usize,
+    /// エラーの種類。
+    pub kind: KanaParseErrorKind,
+}
+
+/// AquesTalk風記法をAccentPhraseの配列に解析する。
+///
+/// 音高と音素長は全て0となる。[`crate::Synthesizer::replace_mora_data`]で生成しなおすこと。
+pub fn parse_kana(text: &str) -> std::result::Result<Vec<AccentPhrase>, KanaParseError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut accent_phrases = vec![];
+    let mut phrase_start = 0;
+
+    for (position, &c) in chars.iter().enumerate() {
+        if c != NOPAUSE_DELIMITER && c != PAUSE_DELIMITER {
+            continue;
+        }
+        let mut accent_phrase = parse_accent_phrase(&chars[phrase_start..position], phrase_start)?;
+        if c == PAUSE_DELIMITER {
+            accent_phrase.pause_mora = Some(pause_mora());
+        }
+        accent_phrases.push(accent_phrase);
+        phrase_start = position + 1;
+    }
+    accent_phrases.push(parse_accent_phrase(&chars[phrase_start..], phrase_start)?);
+
+    Ok(accent_phrases)
+}
+
+/// AccentPhraseの配列をAquesTalk風記法に変換する。
+///
+/// [`crate::AudioQuery::kana`]と同じ形式となる。
+pub fn create_kana(accent_phrases: &[AccentPhrase]) -> String {
+    let mut text = String::new();
+    for (index, accent_phrase) in accent_phrases.iter().enumerate() {
+        if index > 0 {
+            let previous = &accent_phrases[index - 1];
+            text.push(if previous.pause_mora.is_some() {
+                PAUSE_DELIMITER
+            } else {
+                NOPAUSE_DELIMITER
+            });
+        }
+        for (mora_index, mora) in accent_phrase.moras.iter().enumerate() {
+            if ["A", "I", "U", "E", "O"].contains(&mora.vowel.as_str()) {
+                text.push(UNVOICE_SYMBOL);
+            }
+            text.push_str(&mora.text);
+            if mora_index + 1 == accent_phrase.accent {
+                text.push(ACCENT_SYMBOL);
+            }
+        }
+        if accent_phrase.is_interrogative {
+            text.push(WIDE_INTERROGATION_MARK);
+        }
+    }
+
+    text
+}
+
+fn parse_accent_phrase(
+    chars: &[char],
+    offset: usize,
+) -> std::result::Result<AccentPhrase, KanaParseError> {
+    let error = |index: usize, kind| KanaParseError {
+        position: offset + index,
+        kind,
+    };
+    if chars.is_empty() {
+        return Err(error(0, KanaParseErrorKind::EmptyAccentPhrase));
+    }
+
+    let mut moras = vec![];
+    let mut accent = None;
+    let mut is_interrogative = false;
+    let mut index = 0;
+    while index < chars.len() {
+        match chars[index] {
+            ACCENT_SYMBOL => {
+                if moras.is_empty() {
+                    return Err(error(index, KanaParseErrorKind::AccentAtStart));
+                }
+                if accent.is_some() {
+                    return Err(error(index, KanaParseErrorKind::DuplicateAccent));
+                }
+                accent = Some(moras.len());
+                index += 1;
+            }
+            WIDE_INTERROGATION_MARK => {
+                if index + 1 != chars.len() {
+                    return Err(error(index, KanaParseErrorKind::InterrogativeNotAtEnd));
+                }
+                is_interrogative = true;
+                index += 1;
+            }
+            UNVOICE_SYMBOL => {
+                let (mut mora, len) = match_mora(&chars[index + 1..])
+                    .ok_or_else(|| error(index, KanaParseErrorKind::InvalidUnvoice))?;
+                if !["a", "i", "u", "e", "o"].contains(&mora.vowel.as_str()) {
+                    return Err(error(index, KanaParseErrorKind::InvalidUnvoice));
+                }
+                mora.vowel = mora.vowel.to_uppercase();
+                moras.push(mora);
+                index += 1 + len;
+            }
+            c => {
+                let (mora, len) = match_mora(&chars[index..])
+                    .ok_or_else(|| error(index, KanaParseErrorKind::UnknownText(c.to_string())))?;
+                moras.push(mora);
+                index += len;
+            }
+        }
+    }
+
+    if moras.is_empty() {
+        return Err(error(0, KanaParseErrorKind::EmptyAccentPhrase));
+    }
+    let accent = accent.ok_or_else(|| error(chars.len(), KanaParseErrorKind::MissingAccent))?;
+
+    Ok(AccentPhrase {
+        moras,
+        accent,
+        pause_mora: None,
+        is_interrogative,
+    })
+}
+
+/// 先頭から最長一致でモーラを探し、モーラとその文字数を返す。
+fn match_mora(chars: &[char]) -> Option<(MoraModel, usize)> {
+    (1..=chars.len().min(2)).rev().find_map(|len| {
+        let text: String = chars[..len].iter().collect();
+        MORA_LIST
+            .iter()
+            .find(|(mora_text, _, _)| *mora_text == text)
+            .map(|(mora_text, consonant, vowel)| {
+                let consonant = (!consonant.is_empty()).then(|| consonant.to_string());
+                let mora = MoraModel {
+                    text: mora_text.to_string(),
+                    consonant_length: consonant.as_ref().map(|_| 0.0),
+                    consonant,
+                    vowel: vowel.to_string(),
+                    vowel_length: 0.0,
+                    pitch: 0.0,
+                };
+                (mora, len)
+            })
+    })
+}
+
+fn pause_mora() -> MoraModel {
+    MoraModel {
+        text: PAUSE_DELIMITER.to_string(),
+        consonant: None,
+        consonant_length: None,
+        vowel: "pau".to_string(),
+        vowel_length: 0.0,
+        pitch: 0.0,
+    }
+}
@@ -1,15 +1,64 @@
+mod accent_suggestion;
+mod asset;
+mod blend;
+mod corpus_scan;
+#[cfg(feature = "embed")]
+mod embedded;
+mod engine_dict;
+mod english;
 mod info;
+mod kana;
+mod merge;
+mod midi;
+mod model_registry;
 mod models;
+mod normalize;
 mod open_jtalk;
+mod preset;
+mod project;
+mod pronunciation_test;
+mod replace;
 mod result;
+mod sanitize;
+mod ssml;
 mod synthesizer;
+mod tenant;
 mod user_dict;
+mod user_dict_format;
+mod user_dict_merge;
+mod user_dict_watcher;
+mod user_dict_word;
+mod validation;
 mod voice_model;
+mod wav;
 
+pub use accent_suggestion::*;
+pub use asset::*;
+pub use blend::*;
+pub use corpus_scan::*;
+pub use engine_dict::*;
+pub use english::*;
 pub use info::*;
+pub use kana::*;
+pub use merge::*;
+pub use midi::*;
+pub use model_registry::*;
 pub use models::*;
+pub use normalize::*;
 pub use open_jtalk::*;
+pub use preset::*;
+pub use project::*;
+pub use pronunciation_test::*;
+pub use replace::*;
 pub use result::*;
+pub use sanitize::*;
+pub use ssml::*;
 pub use synthesizer::*;
+pub use tenant::*;
 pub use user_dict::*;
+pub use user_dict_format::*;
+pub use user_dict_merge::*;
+pub use user_dict_watcher::*;
+pub use user_dict_word::*;
+pub use validation::*;
 pub use voice_model::*;
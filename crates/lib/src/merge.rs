@@ -0,0 +1,80 @@
+use crate::{create_kana, AccentPhrase, AudioQuery};
+
+impl AudioQuery {
+    /// 編集済みのAudioQueryの調整を、テキストを変更して生成しなおしたAudioQueryに引き継ぐ。
+    ///
+    /// アクセント句はモーラのテキストで対応付け、変更されていないアクセント句の音高・音素長・アクセ
+    /// ントを`self`から引き継ぐ。話速などの全体の設定も`self`から引き継ぐ。
+    ///
+    /// # Arguments
+    /// * `fresh` - 変更後のテキストから生成しなおしたAudioQuery。
+    pub fn merge_edits(&self, fresh: &AudioQuery) -> AudioQuery {
+        let accent_phrases = merge_accent_phrases(&self.accent_phrases, &fresh.accent_phrases);
+        let kana = fresh.kana.as_ref().map(|_| create_kana(&accent_phrases));
+
+        AudioQuery {
+            accent_phrases,
+            kana,
+            ..self.clone()
+        }
+    }
+}
+
+/// 編集済みのAccentPhraseの配列の調整を、生成しなおしたAccentPhraseの配列に引き継ぐ。
+///
+/// [`AudioQuery::merge_edits`]を参照。
+pub fn merge_accent_phrases(edited: &[AccentPhrase], fresh: &[AccentPhrase]) -> Vec<AccentPhrase> {
+    let edited_texts: Vec<String> = edited.iter().map(accent_phrase_text).collect();
+    let fresh_texts: Vec<String> = fresh.iter().map(accent_phrase_text).collect();
+
+    // 最長共通部分列で対応付ける。
+    let mut lengths = vec![vec![0usize; fresh.len() + 1]; edited.len() + 1];
+    for i in (0..edited.len()).rev() {
+        for j in (0..fresh.len()).rev() {
+            lengths[i][j] = if edited_texts[i] == fresh_texts[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut merged = fresh.to_vec();
+    let (mut i, mut j) = (0, 0);
+    while i < edited.len() && j < fresh.len() {
+        if edited_texts[i] == fresh_texts[j] {
+            merged[j] = merge_accent_phrase(&edited[i], &fresh[j]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    merged
+}
+
+fn accent_phrase_text(accent_phrase: &AccentPhrase) -> String {
+    accent_phrase
+        .moras
+        .iter()
+        .map(|mora| mora.text.as_str())
+        .collect()
+}
+
+fn merge_accent_phrase(edited: &AccentPhrase, fresh: &AccentPhrase) -> AccentPhrase {
+    // 句読点は変わっている可能性があるので、無音の有無は新しい方に従う。
+    let pause_mora = match (&edited.pause_mora, &fresh.pause_mora) {
+        (Some(edited), Some(_)) => Some(edited.clone()),
+        (_, fresh) => fresh.clone(),
+    };
+
+    AccentPhrase {
+        moras: edited.moras.clone(),
+        accent: edited.accent,
+        pause_mora,
+        is_interrogative: fresh.is_interrogative,
+    }
+}
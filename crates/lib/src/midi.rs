@@ -0,0 +1,465 @@
+use crate::AudioQuery;
+use midly::{
+    num::{u15, u24, u28, u4, u7},
+    Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind,
+};
+use thiserror::Error;
+
+/// MIDIの読み書きのエラー。
+#[derive(Error, Debug)]
+pub enum MidiError {
+    /// SMFとしての解析に失敗した
+    #[error("SMFとしての解析に失敗した")]
+    Parse(#[from] midly::Error),
+
+    /// SMFの書き込みに失敗した
+    #[error("SMFの書き込みに失敗した")]
+    Write(#[from] std::io::Error),
+
+    /// 対応していない時間単位
+    #[error("SMPTEタイムコードの時間単位には対応していない")]
+    UnsupportedTiming,
+
+    /// ノートが無い
+    #[error("トラック{0}にノートが無い")]
+    NoNotes(usize),
+}
+
+/// 周波数（Hz）を、モーラの音高の単位（Hzの自然対数）に変換する。
+pub fn hz_to_log_f0(hz: f32) -> f32 {
+    hz.ln()
+}
+
+/// モーラの音高の単位（Hzの自然対数）を、周波数（Hz）に変換する。
+pub fn log_f0_to_hz(log_f0: f32) -> f32 {
+    log_f0.exp()
+}
+
+/// MIDIのノート番号を周波数（Hz）に変換する。69番（A4）が440Hz。
+pub fn note_to_hz(note: f32) -> f32 {
+    440.0 * 2f32.powf((note - 69.0) / 12.0)
+}
+
+/// 周波数（Hz）をMIDIのノート番号に変換する。
+pub fn hz_to_note(hz: f32) -> f32 {
+    69.0 + 12.0 * (hz / 440.0).log2()
+}
+
+/// MIDIのノート番号を、モーラの音高の単位に変換する。
+pub fn note_to_log_f0(note: f32) -> f32 {
+    hz_to_log_f0(note_to_hz(note))
+}
+
+/// モーラの音高の単位を、MIDIのノート番号に変換する。
+pub fn log_f0_to_note(log_f0: f32) -> f32 {
+    hz_to_note(log_f0_to_hz(log_f0))
+}
+
+/// テンポマップ。tickと秒の変換を行う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TempoMap {
+    /// 四分音符あたりのtick数。
+    pub ticks_per_quarter: u16,
+    /// テンポ変更の`(tick, 四分音符あたりのマイクロ秒数)`の組。tickの昇順。
+    pub changes: Vec<(u64, u32)>,
+}
+
+impl TempoMap {
+    /// テンポが一定のテンポマップを作成する。
+    pub fn constant(bpm: f64, ticks_per_quarter: u16) -> Self {
+        Self {
+            ticks_per_quarter,
+            changes: vec![(0, (60_000_000.0 / bpm).round() as u32)],
+        }
+    }
+
+    /// テンポ変更のリストからテンポマップを作成する。tick 0にテンポが無い場合は120BPMとする。
+    pub fn new(ticks_per_quarter: u16, mut changes: Vec<(u64, u32)>) -> Self {
+        changes.sort_by_key(|(tick, _)| *tick);
+        if changes.first().map(|(tick, _)| *tick) != Some(0) {
+            changes.insert(0, (0, 500_000));
+        }
+        Self {
+            ticks_per_quarter,
+            changes,
+        }
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (u64, u32, f64)> + '_ {
+        // (開始tick, テンポ, 開始秒)
+        let mut seconds = 0.0;
+        let mut previous: Option<(u64, u32)> = None;
+        self.changes.iter().map(move |&(tick, tempo)| {
+            if let Some((previous_tick, previous_tempo)) = previous {
+                seconds += self.ticks_to_seconds(tick - previous_tick, previous_tempo);
+            }
+            previous = Some((tick, tempo));
+            (tick, tempo, seconds)
+        })
+    }
+
+    fn ticks_to_seconds(&self, ticks: u64, tempo: u32) -> f64 {
+        ticks as f64 * tempo as f64 / 1_000_000.0 / self.ticks_per_quarter as f64
+    }
+
+    /// tickを秒に変換する。
+    pub fn tick_to_seconds(&self, tick: u64) -> f64 {
+        let (start_tick, tempo, start_seconds) = self
+            .segments()
+            .take_while(|(start_tick, _, _)| *start_tick <= tick)
+            .last()
+            .unwrap_or((0, 500_000, 0.0));
+        start_seconds + self.ticks_to_seconds(tick - start_tick, tempo)
+    }
+
+    /// 秒をtickに変換する。
+    pub fn seconds_to_tick(&self, seconds: f64) -> u64 {
+        let (start_tick, tempo, start_seconds) = self
+            .segments()
+            .take_while(|(_, _, start_seconds)| *start_seconds <= seconds)
+            .last()
+            .unwrap_or((0, 500_000, 0.0));
+        let ticks =
+            (seconds - start_seconds) * 1_000_000.0 * self.ticks_per_quarter as f64 / tempo as f64;
+        start_tick + ticks.round().max(0.0) as u64
+    }
+}
+
+/// 秒単位のノート。
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiNote {
+    /// ノート番号。
+    pub key: u8,
+    /// ベロシティ。
+    pub velocity: u8,
+    /// 開始時刻（秒）。
+    pub start: f64,
+    /// 長さ（秒）。
+    pub duration: f64,
+    /// 歌詞。
+    pub lyric: Option<String>,
+}
+
+/// tick単位の、終了が未確定のノート。
+struct PendingNote {
+    key: u8,
+    velocity: u8,
+    start: u64,
+    end: Option<u64>,
+    lyric: Option<String>,
+}
+
+/// SMFを読み込み、指定したトラックのノートとテンポマップを返す。
+///
+/// ノートが重なっている場合は、後のノートの開始時刻で前のノートを切る。テンポ変更は全トラックから
+/// 集める。
+pub fn read_midi(
+    bytes: &[u8],
+    track: usize,
+) -> std::result::Result<(Vec<MidiNote>, TempoMap), MidiError> {
+    let smf = Smf::parse(bytes)?;
+    let Timing::Metrical(ticks_per_quarter) = smf.header.timing else {
+        return Err(MidiError::UnsupportedTiming);
+    };
+
+    let mut tempo_changes = vec![];
+    for events in &smf.tracks {
+        let mut tick = 0u64;
+        for event in events {
+            tick += event.delta.as_int() as u64;
+            if let TrackEventKind::Meta(MetaMessage::Tempo(tempo)) = event.kind {
+                tempo_changes.push((tick, tempo.as_int()));
+            }
+        }
+    }
+    let tempo_map = TempoMap::new(ticks_per_quarter.as_int(), tempo_changes);
+
+    let mut notes: Vec<PendingNote> = vec![];
+    let mut lyric = None;
+    let mut tick = 0u64;
+    for event in smf.tracks.get(track).into_iter().flatten() {
+        tick += event.delta.as_int() as u64;
+        match event.kind {
+            TrackEventKind::Meta(MetaMessage::Lyric(text)) => {
+                lyric = Some(String::from_utf8_lossy(text).into_owned());
+            }
+            TrackEventKind::Midi {
+                message: MidiMessage::NoteOn { key, vel },
+                ..
+            } if vel.as_int() > 0 => {
+                notes.push(PendingNote {
+                    key: key.as_int(),
+                    velocity: vel.as_int(),
+                    start: tick,
+                    end: None,
+                    lyric: lyric.take(),
+                });
+            }
+            TrackEventKind::Midi {
+                message: MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. },
+                ..
+            } => {
+                if let Some(note) = notes
+                    .iter_mut()
+                    .rev()
+                    .find(|note| note.key == key.as_int() && note.end.is_none())
+                {
+                    note.end = Some(tick);
+                }
+            }
+            _ => {}
+        }
+    }
+    if notes.is_empty() {
+        return Err(MidiError::NoNotes(track));
+    }
+
+    notes.sort_by_key(|note| note.start);
+    let next_starts: Vec<Option<u64>> = (0..notes.len())
+        .map(|index| notes.get(index + 1).map(|note| note.start))
+        .collect();
+    let notes = notes
+        .into_iter()
+        .zip(next_starts)
+        .map(|(note, next_start)| {
+            let end = match (note.end, next_start) {
+                (Some(end), Some(next_start)) => end.min(next_start),
+                (Some(end), None) => end,
+                (None, next_start) => next_start.unwrap_or(tick),
+            };
+            let start_seconds = tempo_map.tick_to_seconds(note.start);
+            MidiNote {
+                key: note.key,
+                velocity: note.velocity,
+                start: start_seconds,
+                duration: tempo_map.tick_to_seconds(end) - start_seconds,
+                lyric: note.lyric,
+            }
+        })
+        .collect();
+
+    Ok((notes, tempo_map))
+}
+
+/// ノートを1トラックのSMFに書き出す。
+pub fn write_midi(
+    notes: &[MidiNote],
+    tempo_map: &TempoMap,
+) -> std::result::Result<Vec<u8>, MidiError> {
+    let ticks_per_quarter = u15::new(tempo_map.ticks_per_quarter.min(0x7fff));
+    let channel = u4::new(0);
+
+    // (tick, 優先順位, イベント)。同じtickではテンポ、ノートオフ、歌詞、ノートオンの順にする。
+    let mut events: Vec<(u64, u8, TrackEventKind)> = vec![];
+    for &(tick, tempo) in &tempo_map.changes {
+        events.push((
+            tick,
+            0,
+            TrackEventKind::Meta(MetaMessage::Tempo(u24::new(tempo))),
+        ));
+    }
+    for note in notes {
+        let start = tempo_map.seconds_to_tick(note.start);
+        let end = tempo_map
+            .seconds_to_tick(note.start + note.duration)
+            .max(start + 1);
+        let key = u7::new(note.key.min(127));
+        if let Some(lyric) = &note.lyric {
+            events.push((
+                start,
+                2,
+                TrackEventKind::Meta(MetaMessage::Lyric(lyric.as_bytes())),
+            ));
+        }
+        events.push((
+            start,
+            3,
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::NoteOn {
+                    key,
+                    vel: u7::new(note.velocity.clamp(1, 127)),
+                },
+            },
+        ));
+        events.push((
+            end,
+            1,
+            TrackEventKind::Midi {
+                channel,
+                message: MidiMessage::NoteOff {
+                    key,
+                    vel: u7::new(0),
+                },
+            },
+        ));
+    }
+    events.sort_by_key(|(tick, priority, _)| (*tick, *priority));
+
+    let mut track = vec![];
+    let mut previous_tick = 0;
+    for (tick, _, kind) in events {
+        track.push(TrackEvent {
+            delta: u28::new((tick - previous_tick) as u32),
+            kind,
+        });
+        previous_tick = tick;
+    }
+    track.push(TrackEvent {
+        delta: u28::new(0),
+        kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+    });
+
+    let mut smf = Smf::new(Header::new(
+        Format::SingleTrack,
+        Timing::Metrical(ticks_per_quarter),
+    ));
+    smf.tracks.push(track);
+    let mut bytes = vec![];
+    smf.write_std(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+impl AudioQuery {
+    /// AudioQueryの韻律をノートとして書き出す。
+    ///
+    /// モーラ1つが1つのノートとなる。無声のモーラはノートにしない。音高はコアと同じく、
+    /// [`AudioQuery::pitch_scale`]で`2^pitch_scale`倍し、[`AudioQuery::intonation_scale`]で有声の
+    /// モーラの音高の平均を中心に広げたものにする。時刻は[`AudioQuery::speed_scale`]を反映し、
+    /// ノート番号は最も近い整数に丸める。
+    pub fn to_midi_notes(&self) -> Vec<MidiNote> {
+        let pitch_scale = 2f32.powf(self.pitch_scale);
+        let voiced: Vec<f32> = self
+            .accent_phrases
+            .iter()
+            .flat_map(|accent_phrase| &accent_phrase.moras)
+            .filter(|mora| mora.pitch > 0.0)
+            .map(|mora| mora.pitch * pitch_scale)
+            .collect();
+        let mean_pitch = voiced.iter().sum::<f32>() / voiced.len().max(1) as f32;
+
+        let mut notes = vec![];
+        let mut time = (self.pre_phoneme_length / self.speed_scale) as f64;
+        for accent_phrase in &self.accent_phrases {
+            for mora in &accent_phrase.moras {
+                let duration = ((mora.consonant_length.unwrap_or(0.0) + mora.vowel_length)
+                    / self.speed_scale) as f64;
+                if mora.pitch > 0.0 {
+                    let pitch = (mora.pitch * pitch_scale - mean_pitch) * self.intonation_scale
+                        + mean_pitch;
+                    let key = log_f0_to_note(pitch).round();
+                    notes.push(MidiNote {
+                        key: key.clamp(0.0, 127.0) as u8,
+                        velocity: 100,
+                        start: time,
+                        duration,
+                        lyric: Some(mora.text.clone()),
+                    });
+                }
+                time += duration;
+            }
+            if let Some(pause_mora) = &accent_phrase.pause_mora {
+                time += (pause_mora.vowel_length / self.speed_scale) as f64;
+            }
+        }
+
+        notes
+    }
+
+    /// ノートに合わせてモーラの音高と音素長を書き換える。
+    ///
+    /// [`AudioQuery::to_midi_notes`]の逆の変換で、有声のモーラと、歌詞がモーラのテキストと一致する
+    /// ノートのモーラに、ノートを順に割り当てる。無声化したモーラや促音は、歌詞が一致するノートが
+    /// 無ければ割り当てずに残す。ノートを割り当てたモーラは、音高をノートの音高に、子音と母音の長さの
+    /// 合計をノートの長さにする。子音の長さはノートの長さの半分までに縮め、無声化した母音は有声に
+    /// 戻す。ノートの前の休符は、直前の無音モーラ、モーラの母音か[`AudioQuery::pre_phoneme_length`]
+    /// に加える。時刻と音高をそのまま反映するため、話速・音高・抑揚の全体の設定は既定値に戻す。
+    ///
+    /// # Returns
+    /// ノートを割り当てたモーラの数。
+    pub fn apply_midi_notes(&mut self, notes: &[MidiNote]) -> usize {
+        let speed_scale = self.speed_scale;
+        self.speed_scale = 1.0;
+        self.pitch_scale = 0.0;
+        self.intonation_scale = 1.0;
+        // ノートを割り当てないところは、話速を反映した長さにして時刻を保つ。
+        self.pre_phoneme_length /= speed_scale;
+
+        let mut rest_slot = RestSlot::PrePhoneme;
+        let mut time = self.pre_phoneme_length as f64;
+        let mut notes = notes.iter().peekable();
+        let mut assigned = 0;
+        for phrase_index in 0..self.accent_phrases.len() {
+            for mora_index in 0..self.accent_phrases[phrase_index].moras.len() {
+                let mora = &self.accent_phrases[phrase_index].moras[mora_index];
+                let Some(note) = notes.next_if(|note| {
+                    mora.pitch > 0.0 || note.lyric.as_deref() == Some(mora.text.as_str())
+                }) else {
+                    let mora = &mut self.accent_phrases[phrase_index].moras[mora_index];
+                    if let Some(consonant_length) = &mut mora.consonant_length {
+                        *consonant_length /= speed_scale;
+                    }
+                    mora.vowel_length /= speed_scale;
+                    // 休符は割り当てなかったモーラの前後で区別せず、直前の休符の場所に加える。
+                    time += (mora.consonant_length.unwrap_or(0.0) + mora.vowel_length) as f64;
+                    continue;
+                };
+
+                let rest = self.rest_slot_length(rest_slot);
+                *rest = (*rest + (note.start - time) as f32).max(0.0);
+
+                let mora = &mut self.accent_phrases[phrase_index].moras[mora_index];
+                let duration = note.duration as f32;
+                let consonant_length = mora
+                    .consonant_length
+                    .map(|consonant_length| consonant_length.min(duration / 2.0));
+                mora.consonant_length = consonant_length;
+                mora.vowel_length = duration - consonant_length.unwrap_or(0.0);
+                if matches!(mora.vowel.as_str(), "A" | "I" | "U" | "E" | "O") {
+                    mora.vowel = mora.vowel.to_lowercase();
+                }
+                mora.pitch = note_to_log_f0(note.key as f32);
+                time = note.start + note.duration;
+                rest_slot = RestSlot::Mora(phrase_index, mora_index);
+                assigned += 1;
+            }
+            if let Some(pause_mora) = &mut self.accent_phrases[phrase_index].pause_mora {
+                // 次のノートまでの休符を無音モーラの長さにする。
+                if notes.peek().is_some() {
+                    pause_mora.vowel_length = 0.0;
+                } else {
+                    pause_mora.vowel_length /= speed_scale;
+                    time += pause_mora.vowel_length as f64;
+                }
+                rest_slot = RestSlot::Pause(phrase_index);
+            }
+        }
+
+        assigned
+    }
+
+    fn rest_slot_length(&mut self, rest_slot: RestSlot) -> &mut f32 {
+        match rest_slot {
+            RestSlot::PrePhoneme => &mut self.pre_phoneme_length,
+            RestSlot::Mora(phrase_index, mora_index) => {
+                &mut self.accent_phrases[phrase_index].moras[mora_index].vowel_length
+            }
+            RestSlot::Pause(phrase_index) => {
+                &mut self.accent_phrases[phrase_index]
+                    .pause_mora
+                    .as_mut()
+                    .unwrap()
+                    .vowel_length
+            }
+        }
+    }
+}
+
+/// [`AudioQuery::apply_midi_notes`]で、ノートの前の休符を加える場所。
+#[derive(Clone, Copy)]
+enum RestSlot {
+    PrePhoneme,
+    Mora(usize, usize),
+    Pause(usize),
+}
@@ -0,0 +1,270 @@
+use crate::{SpeakerMeta, StyleId, Synthesizer, VoiceModel, VoicevoxError};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use thiserror::Error;
+
+/// キャッシュの形式のバージョン。形式を変えたら上げる。
+const CACHE_VERSION: u32 = 1;
+
+/// [`ModelRegistry`]のエラー。
+#[derive(Error, Debug)]
+pub enum ModelRegistryError {
+    /// ディレクトリやVVMファイルの読み込みに失敗した
+    #[error("ディレクトリやVVMファイルの読み込みに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// キャッシュのJSONへの変換に失敗した
+    #[error("キャッシュのJSONへの変換に失敗した")]
+    Json(#[from] serde_json::Error),
+
+    /// 音声モデルの読み込みに失敗した
+    #[error("音声モデルの読み込みに失敗した：{path}")]
+    Voicevox {
+        /// 読み込めなかったVVMファイルへのパス。
+        path: PathBuf,
+        /// コアのエラー。
+        #[source]
+        source: VoicevoxError,
+    },
+
+    /// 複数の音声モデルが同じスタイルIDを持っている
+    #[error("スタイルID {style_id} が {first} と {second} の両方にある")]
+    DuplicateStyleId {
+        /// 重複したスタイルID。
+        style_id: StyleId,
+        /// スタイルIDを持つ、ファイル名の順で最初のVVMファイルへのパス。
+        first: PathBuf,
+        /// 同じスタイルIDを持つ、もう一つのVVMファイルへのパス。
+        second: PathBuf,
+    },
+
+    /// どの音声モデルにも無いスタイルID
+    #[error("どの音声モデルにも無いスタイルID：{0}")]
+    UnknownStyleId(StyleId),
+}
+
+/// [`ModelRegistry`]が見つけた音声モデル。
+#[derive(Debug, Clone)]
+pub struct ModelRegistryEntry {
+    /// VVMファイルへのパス。
+    pub path: PathBuf,
+    /// 音声モデルのID。
+    pub id: String,
+    /// 音声モデルに含まれる話者のメタ情報。
+    pub metas: Vec<SpeakerMeta>,
+}
+
+/// VVMファイルの更新を見分けるための、大きさと更新日時。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+struct FileSignature {
+    len: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl FileSignature {
+    fn of(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Ok(Self {
+            len: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct CachedModel {
+    file_name: String,
+    signature: FileSignature,
+    id: String,
+    metas: Vec<SpeakerMeta>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Cache {
+    version: u32,
+    models: Vec<CachedModel>,
+}
+
+/// ディレクトリ内のVVMファイルを管理し、スタイルが初めて使われたときに音声モデルを読み込む。
+///
+/// 作成するときはメタ情報だけを読み、スタイルIDから音声モデルへの索引を作る。音声モデルは
+/// [`ModelRegistry::load_style`]で初めて必要になったときに開いて`Synthesizer`に読み込む。
+/// [`ModelRegistry::scan_with_cache`]を使うと、メタ情報をファイルにキャッシュし、次に起動する
+/// ときは更新されていないVVMファイルを開かずに済ませる。
+pub struct ModelRegistry {
+    entries: Vec<ModelRegistryEntry>,
+    styles: HashMap<StyleId, usize>,
+    /// 開いた音声モデル。`entries`と同じ順に並ぶ。
+    models: Vec<Option<VoiceModel>>,
+}
+
+impl ModelRegistry {
+    /// ディレクトリ内のVVMファイルをすべて開いてメタ情報を読む。
+    pub fn scan<S: AsRef<Path>>(dir: S) -> Result<Self, ModelRegistryError> {
+        let mut cache = vec![];
+        Self::scan_inner(dir.as_ref(), &mut cache)
+    }
+
+    /// [`ModelRegistry::scan`]と同じだが、メタ情報を`cache_path`のJSONファイルにキャッシュする。
+    ///
+    /// 大きさと更新日時がキャッシュと同じVVMファイルは開かない。キャッシュが無い場合や読めない場合は
+    /// すべてのVVMファイルを開き、キャッシュを作りなおす。キャッシュは起動を速くするためだけのものなので、
+    /// 読み取り専用のディレクトリなどで書き込めなくてもエラーにはしない。
+    pub fn scan_with_cache<S: AsRef<Path>, C: AsRef<Path>>(
+        dir: S,
+        cache_path: C,
+    ) -> Result<Self, ModelRegistryError> {
+        let cache_path = cache_path.as_ref();
+        let mut cache = std::fs::read(cache_path)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<Cache>(&json).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .map(|cache| cache.models)
+            .unwrap_or_default();
+        let before = serde_json::to_string(&cache)?;
+
+        let registry = Self::scan_inner(dir.as_ref(), &mut cache)?;
+
+        let cache = Cache {
+            version: CACHE_VERSION,
+            models: cache,
+        };
+        if serde_json::to_string(&cache.models)? != before {
+            let _ = std::fs::write(cache_path, serde_json::to_string(&cache)?);
+        }
+
+        Ok(registry)
+    }
+
+    /// `cache`にあるメタ情報を使い、無いものや古いものはVVMファイルを開いて読む。
+    ///
+    /// `cache`は見つかったVVMファイルのものだけに置き換える。
+    fn scan_inner(dir: &Path, cache: &mut Vec<CachedModel>) -> Result<Self, ModelRegistryError> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<_>>()?;
+        paths.retain(|path| path.is_file() && path.extension().is_some_and(|e| e == "vvm"));
+        paths.sort();
+
+        let mut cached: HashMap<String, CachedModel> = cache
+            .drain(..)
+            .map(|model| (model.file_name.clone(), model))
+            .collect();
+        let mut entries = Vec::with_capacity(paths.len());
+        for path in paths {
+            let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let signature = FileSignature::of(&path)?;
+            let model = match cached.remove(&file_name) {
+                Some(model) if model.signature == signature => model,
+                _ => {
+                    let voice_model = VoiceModel::from_path(&path).map_err(|source| {
+                        ModelRegistryError::Voicevox {
+                            path: path.clone(),
+                            source,
+                        }
+                    })?;
+                    CachedModel {
+                        file_name,
+                        signature,
+                        id: voice_model.id(),
+                        metas: voice_model.metas(),
+                    }
+                }
+            };
+            entries.push(ModelRegistryEntry {
+                path,
+                id: model.id.clone(),
+                metas: model.metas.clone(),
+            });
+            cache.push(model);
+        }
+
+        let mut styles = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            for style in entry.metas.iter().flat_map(|meta| meta.styles()) {
+                if let Some(first) = styles.insert(style.id(), index) {
+                    return Err(ModelRegistryError::DuplicateStyleId {
+                        style_id: style.id(),
+                        first: entries[first].path.clone(),
+                        second: entry.path.clone(),
+                    });
+                }
+            }
+        }
+
+        let models = entries.iter().map(|_| None).collect();
+        Ok(Self {
+            entries,
+            styles,
+            models,
+        })
+    }
+
+    /// 見つかった音声モデル。ファイル名の順に並ぶ。
+    pub fn entries(&self) -> &[ModelRegistryEntry] {
+        &self.entries
+    }
+
+    /// すべての音声モデルの話者のメタ情報。音声モデルを読み込まずに話者の一覧を返すのに使う。
+    pub fn metas(&self) -> Vec<SpeakerMeta> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.metas.iter().cloned())
+            .collect()
+    }
+
+    /// スタイルIDを含む音声モデル。
+    pub fn entry_for_style(&self, style_id: StyleId) -> Option<&ModelRegistryEntry> {
+        self.styles
+            .get(&style_id)
+            .map(|&index| &self.entries[index])
+    }
+
+    /// スタイルIDを含む音声モデルが`synthesizer`に読み込まれているかどうか。
+    pub fn is_style_loaded(&self, synthesizer: &Synthesizer, style_id: StyleId) -> bool {
+        self.styles
+            .get(&style_id)
+            .and_then(|&index| self.models[index].as_ref())
+            .is_some_and(|model| synthesizer.is_loaded_voice_model(model))
+    }
+
+    /// スタイルIDを含む音声モデルを、まだ読み込まれていなければ`synthesizer`に読み込む。
+    ///
+    /// 音声合成の前に呼ぶ。開いた音声モデルはレジストリが持ち続け、ほかの`Synthesizer`にも使う。
+    pub fn load_style(
+        &mut self,
+        synthesizer: &Synthesizer,
+        style_id: StyleId,
+    ) -> Result<(), ModelRegistryError> {
+        let index = *self
+            .styles
+            .get(&style_id)
+            .ok_or(ModelRegistryError::UnknownStyleId(style_id))?;
+        let path = &self.entries[index].path;
+        let to_error = |source| ModelRegistryError::Voicevox {
+            path: path.clone(),
+            source,
+        };
+
+        let model = match &mut self.models[index] {
+            Some(model) => model,
+            model => model.insert(VoiceModel::from_path(path).map_err(to_error)?),
+        };
+        if !synthesizer.is_loaded_voice_model(model) {
+            synthesizer.load_voice_model(model).map_err(to_error)?;
+        }
+
+        Ok(())
+    }
+}
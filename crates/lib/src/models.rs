@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// モーラ（子音＋母音）ごとの情報。
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct MoraModel {
     /// 文字。
     pub text: String,
@@ -18,7 +18,7 @@ pub struct MoraModel {
 }
 
 /// AccentPhrase (アクセント句ごとの情報)。
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct AccentPhrase {
     /// モーラの配列。
     pub moras: Vec<MoraModel>,
@@ -32,7 +32,7 @@ pub struct AccentPhrase {
 }
 
 /// AudioQuery (音声合成用のクエリ)。
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct AudioQuery {
     /// アクセント句の配列。
     pub accent_phrases: Vec<AccentPhrase>,
@@ -0,0 +1,959 @@
+use crate::{AudioQuery, EnglishTransliterator, Result, StyleId, Synthesizer, TtsOptions};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// テキスト正規化の規則ごとの有効・無効。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    /// 全角の英数字・記号を半角にする。
+    pub width: bool,
+    /// 数字と助数詞（`3本`→`サンボン`）。
+    pub counters: bool,
+    /// 日付（`2024/1/1`・`令和6年`など）。
+    pub dates: bool,
+    /// 時刻（`12:30`）。
+    pub times: bool,
+    /// 金額（`¥1,000`・`$5`・`100円`）。
+    pub currency: bool,
+    /// 割合（`50%`）。
+    pub percentages: bool,
+    /// URL。
+    pub urls: bool,
+    /// メールアドレス。
+    pub emails: bool,
+    /// 大文字のアルファベットの略語（`NHK`）。
+    pub acronyms: bool,
+    /// 英単語（`voice`→`ボイス`）。
+    pub english: bool,
+    /// 絵文字。
+    pub emoji: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            width: true,
+            counters: true,
+            dates: true,
+            times: true,
+            currency: true,
+            percentages: true,
+            urls: true,
+            emails: true,
+            acronyms: true,
+            english: true,
+            emoji: true,
+        }
+    }
+}
+
+/// Open JTalkに渡す前に、読み間違えやすい表記を読みやすい日本語に書き換えるテキスト正規化器。
+pub struct TextNormalizer {
+    options: NormalizeOptions,
+    emoji: HashMap<String, String>,
+    english: EnglishTransliterator,
+    url: Regex,
+    email: Regex,
+    ymd: Regex,
+    era: Regex,
+    month_day: Regex,
+    time: Regex,
+    currency_prefix: Regex,
+    yen: Regex,
+    percentage: Regex,
+    counter: Regex,
+    latin: Regex,
+}
+
+/// URLを置き換える読み。
+const URL_READING: &str = "ユーアールエル";
+
+/// URLの正規表現。`\w`は日本語にもマッチし、続く文章まで巻き込むので、ASCIIの文字だけを使う。
+pub(crate) const URL_PATTERN: &str = r"https?://[A-Za-z0-9!#$%&'()*+,\-./:;=?@\[\]~_]+";
+
+/// 標準の絵文字の読み。
+const EMOJI_READINGS: &[(&str, &str)] = &[
+    ("😀", "ニッコリ"),
+    ("😊", "ニコニコ"),
+    ("😂", "ワライナキ"),
+    ("🤣", "オオワライ"),
+    ("😭", "ナキ"),
+    ("😢", "カナシイ"),
+    ("😡", "イカリ"),
+    ("😇", "テンシ"),
+    ("🤔", "カンガエチュウ"),
+    ("😱", "ガーン"),
+    ("👍", "イイネ"),
+    ("👀", "メ"),
+    ("🙏", "オネガイ"),
+    ("👏", "ハクシュ"),
+    ("❤", "ハート"),
+    ("🎉", "クラッカー"),
+    ("🔥", "ホノオ"),
+    ("✨", "キラキラ"),
+    ("💦", "アセ"),
+    ("⭐", "ホシ"),
+    ("🍣", "スシ"),
+    ("🍺", "ビール"),
+    ("🐱", "ネコ"),
+    ("🐶", "イヌ"),
+];
+
+/// 助数詞の読み方。
+struct Counter {
+    /// 表記。
+    surface: &'static str,
+    /// 読み。
+    reading: &'static str,
+    /// 数字の読みの末尾が促音化する語尾。
+    geminate: &'static [&'static str],
+    /// 促音化したときに助数詞を半濁音にするかどうか。
+    handakuten_after_geminate: bool,
+    /// 数字の読みが`ン`で終わるときの助数詞の変化。
+    after_n: AfterN,
+    /// 数字の読みが`ヨン`で終わるときに`ヨ`にするかどうか。
+    yo: bool,
+    /// 特別な読み。
+    special: &'static [(u64, &'static str)],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AfterN {
+    Keep,
+    /// 濁音にする。ただし`ヨン`の後は変えない。
+    Voiced,
+    /// 半濁音にする。
+    Handakuten,
+}
+
+/// 促音になる数の読みの終わり。`ャク`はヒャク・ビャク・ピャクのすべてにマッチさせる。
+const ALL_GEMINATE: &[&str] = &["イチ", "ロク", "ハチ", "ジュウ", "ャク"];
+const ST_GEMINATE: &[&str] = &["イチ", "ハチ", "ジュウ"];
+
+const fn counter(surface: &'static str, reading: &'static str) -> Counter {
+    Counter {
+        surface,
+        reading,
+        geminate: &[],
+        handakuten_after_geminate: false,
+        after_n: AfterN::Keep,
+        yo: false,
+        special: &[],
+    }
+}
+
+const COUNTERS: &[Counter] = &[
+    Counter {
+        geminate: ALL_GEMINATE,
+        handakuten_after_geminate: true,
+        after_n: AfterN::Voiced,
+        ..counter("本", "ホン")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        handakuten_after_geminate: true,
+        after_n: AfterN::Voiced,
+        ..counter("匹", "ヒキ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        handakuten_after_geminate: true,
+        after_n: AfterN::Voiced,
+        ..counter("杯", "ハイ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        handakuten_after_geminate: true,
+        after_n: AfterN::Handakuten,
+        ..counter("分", "フン")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("個", "コ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("回", "カイ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        after_n: AfterN::Voiced,
+        ..counter("階", "カイ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("件", "ケン")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        after_n: AfterN::Voiced,
+        ..counter("軒", "ケン")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("ヶ月", "カゲツ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("か月", "カゲツ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("カ月", "カゲツ")
+    },
+    Counter {
+        geminate: ALL_GEMINATE,
+        ..counter("ヵ月", "カゲツ")
+    },
+    Counter {
+        geminate: ST_GEMINATE,
+        ..counter("冊", "サツ")
+    },
+    Counter {
+        geminate: ST_GEMINATE,
+        special: &[(20, "ハタチ")],
+        ..counter("歳", "サイ")
+    },
+    Counter {
+        geminate: ST_GEMINATE,
+        special: &[(20, "ハタチ")],
+        ..counter("才", "サイ")
+    },
+    Counter {
+        geminate: ST_GEMINATE,
+        after_n: AfterN::Voiced,
+        ..counter("足", "ソク")
+    },
+    Counter {
+        geminate: ST_GEMINATE,
+        ..counter("点", "テン")
+    },
+    Counter {
+        geminate: ST_GEMINATE,
+        ..counter("通", "ツウ")
+    },
+    Counter {
+        yo: true,
+        special: &[(1, "ヒトリ"), (2, "フタリ")],
+        ..counter("人", "ニン")
+    },
+    Counter {
+        yo: true,
+        special: &[(7, "シチジカン"), (9, "クジカン")],
+        ..counter("時間", "ジカン")
+    },
+    Counter {
+        yo: true,
+        ..counter("年", "ネン")
+    },
+    counter("枚", "マイ"),
+    counter("台", "ダイ"),
+    counter("秒", "ビョウ"),
+    counter("度", "ド"),
+    counter("倍", "バイ"),
+    counter("位", "イ"),
+];
+
+const YEN: Counter = Counter {
+    yo: true,
+    ..counter("円", "エン")
+};
+
+const MINUTE: Counter = Counter {
+    geminate: ALL_GEMINATE,
+    handakuten_after_geminate: true,
+    after_n: AfterN::Handakuten,
+    ..counter("分", "フン")
+};
+
+const SECOND: Counter = counter("秒", "ビョウ");
+
+const YEAR: Counter = Counter {
+    yo: true,
+    ..counter("年", "ネン")
+};
+
+impl Default for TextNormalizer {
+    fn default() -> Self {
+        Self::new(NormalizeOptions::default())
+    }
+}
+
+impl TextNormalizer {
+    /// テキスト正規化器を作成する。
+    pub fn new(options: NormalizeOptions) -> Self {
+        let mut counter_surfaces: Vec<&str> = COUNTERS.iter().map(|c| c.surface).collect();
+        counter_surfaces.push("日");
+        counter_surfaces.push("つ");
+        counter_surfaces.push("月");
+        // 長いものから順に試す。
+        counter_surfaces.sort_by_key(|surface| std::cmp::Reverse(surface.chars().count()));
+        let counter = format!(
+            r"(\d[\d,]*)(\.\d+)?({})",
+            counter_surfaces
+                .iter()
+                .map(|surface| regex::escape(surface))
+                .collect::<Vec<_>>()
+                .join("|")
+        );
+
+        Self {
+            options,
+            emoji: EMOJI_READINGS
+                .iter()
+                .map(|(emoji, reading)| (emoji.to_string(), reading.to_string()))
+                .collect(),
+            english: EnglishTransliterator::default(),
+            url: Regex::new(URL_PATTERN).unwrap(),
+            // `\w`は日本語にもマッチするので、URLと同じくASCIIの文字だけを使う。
+            email: Regex::new(r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9\-]+(?:\.[A-Za-z0-9\-]+)+").unwrap(),
+            ymd: Regex::new(r"(\d{4})(?:年(\d{1,2})月(\d{1,2})日|[/\-.](\d{1,2})[/\-.](\d{1,2}))")
+                .unwrap(),
+            era: Regex::new(r"(令和|平成|昭和|大正|明治)(\d{1,2}|元)年").unwrap(),
+            month_day: Regex::new(r"(\d{1,2})月(\d{1,2})日").unwrap(),
+            time: Regex::new(r"(\d{1,2}):(\d{2})(?::(\d{2}))?").unwrap(),
+            currency_prefix: Regex::new(r"([¥$€£])\s?(\d[\d,]*)(\.\d+)?").unwrap(),
+            yen: Regex::new(r"(\d[\d,]*)円").unwrap(),
+            percentage: Regex::new(r"(\d[\d,]*)(\.\d+)?\s?[%％]").unwrap(),
+            counter: Regex::new(&counter).unwrap(),
+            latin: Regex::new(r"[A-Za-z]+").unwrap(),
+        }
+    }
+
+    /// 有効な規則を返す。
+    pub fn options(&self) -> NormalizeOptions {
+        self.options
+    }
+
+    /// 絵文字の読みを追加する。既にある場合は上書きする。
+    pub fn add_emoji(&mut self, emoji: &str, reading: &str) {
+        self.emoji.insert(
+            emoji.trim_end_matches('\u{fe0f}').to_string(),
+            reading.to_string(),
+        );
+    }
+
+    /// 英単語の変換器を差し替える。
+    pub fn set_english(&mut self, english: EnglishTransliterator) {
+        self.english = english;
+    }
+
+    /// テキストを正規化する。
+    pub fn normalize(&self, text: &str) -> String {
+        let options = &self.options;
+        let mut text = if options.width {
+            to_half_width(text)
+        } else {
+            text.to_string()
+        };
+
+        if options.urls {
+            text = self.url.replace_all(&text, URL_READING).into_owned();
+        }
+        if options.emails {
+            text = self
+                .email
+                .replace_all(&text, |caps: &Captures| spell_out(&caps[0]))
+                .into_owned();
+        }
+        if options.dates {
+            text = self
+                .ymd
+                .replace_all(&text, |caps: &Captures| {
+                    let year = parse_number(&caps[1]);
+                    let month = caps
+                        .get(2)
+                        .or(caps.get(4))
+                        .map(|m| parse_number(m.as_str()));
+                    let day = caps
+                        .get(3)
+                        .or(caps.get(5))
+                        .map(|m| parse_number(m.as_str()));
+                    match (year, month, day) {
+                        (Some(year), Some(Some(month)), Some(Some(day)))
+                            if (1..=12).contains(&month) && (1..=31).contains(&day) =>
+                        {
+                            format!(
+                                "{}{}{}",
+                                YEAR.read(year),
+                                month_reading(month),
+                                day_of_month_reading(day)
+                            )
+                        }
+                        _ => caps[0].to_string(),
+                    }
+                })
+                .into_owned();
+            text = self
+                .era
+                .replace_all(&text, |caps: &Captures| {
+                    let era = match &caps[1] {
+                        "令和" => "レイワ",
+                        "平成" => "ヘイセイ",
+                        "昭和" => "ショウワ",
+                        "大正" => "タイショウ",
+                        _ => "メイジ",
+                    };
+                    let year = match &caps[2] {
+                        "元" => "ガンネン".to_string(),
+                        year => YEAR.read(parse_number(year).unwrap_or_default()),
+                    };
+                    format!("{}{}", era, year)
+                })
+                .into_owned();
+            text = self
+                .month_day
+                .replace_all(&text, |caps: &Captures| {
+                    match (parse_number(&caps[1]), parse_number(&caps[2])) {
+                        (Some(month), Some(day))
+                            if (1..=12).contains(&month) && (1..=31).contains(&day) =>
+                        {
+                            format!("{}{}", month_reading(month), day_of_month_reading(day))
+                        }
+                        _ => caps[0].to_string(),
+                    }
+                })
+                .into_owned();
+        }
+        if options.times {
+            text = self
+                .time
+                .replace_all(&text, |caps: &Captures| {
+                    let hour = parse_number(&caps[1]).unwrap_or(u64::MAX);
+                    let minute = parse_number(&caps[2]).unwrap_or(u64::MAX);
+                    let second = caps.get(3).map(|m| parse_number(m.as_str()));
+                    if hour > 24 || minute > 59 {
+                        return caps[0].to_string();
+                    }
+                    let mut reading = hour_reading(hour);
+                    match second {
+                        Some(Some(second)) if second <= 59 => {
+                            reading.push_str(&MINUTE.read(minute));
+                            reading.push_str(&SECOND.read(second));
+                        }
+                        Some(_) => return caps[0].to_string(),
+                        None if minute == 0 => {}
+                        None => reading.push_str(&MINUTE.read(minute)),
+                    }
+                    reading
+                })
+                .into_owned();
+        }
+        if options.currency {
+            text = self
+                .currency_prefix
+                .replace_all(&text, |caps: &Captures| {
+                    let Some(integer) = parse_number(&caps[2]) else {
+                        return caps[0].to_string();
+                    };
+                    let unit = match &caps[1] {
+                        "¥" => "エン",
+                        "$" => "ドル",
+                        "€" => "ユーロ",
+                        _ => "ポンド",
+                    };
+                    match caps.get(3) {
+                        Some(decimal) => {
+                            format!("{}{}", decimal_reading(integer, decimal.as_str()), unit)
+                        }
+                        None if unit == "エン" => YEN.read(integer),
+                        None => format!("{}{}", number_reading(integer), unit),
+                    }
+                })
+                .into_owned();
+            text = self
+                .yen
+                .replace_all(&text, |caps: &Captures| {
+                    parse_number(&caps[1]).map_or_else(|| caps[0].to_string(), |n| YEN.read(n))
+                })
+                .into_owned();
+        }
+        if options.percentages {
+            text = self
+                .percentage
+                .replace_all(&text, |caps: &Captures| {
+                    let Some(integer) = parse_number(&caps[1]) else {
+                        return caps[0].to_string();
+                    };
+                    let number = match caps.get(2) {
+                        Some(decimal) => decimal_reading(integer, decimal.as_str()),
+                        None => number_reading(integer),
+                    };
+                    format!("{}パーセント", number)
+                })
+                .into_owned();
+        }
+        if options.counters {
+            text = self
+                .counter
+                .replace_all(&text, |caps: &Captures| {
+                    let Some(integer) = parse_number(&caps[1]) else {
+                        return caps[0].to_string();
+                    };
+                    let surface = &caps[3];
+                    if let Some(decimal) = caps.get(2) {
+                        let reading = match surface {
+                            "日" => "ニチ",
+                            "つ" => return caps[0].to_string(),
+                            "月" => "ガツ",
+                            _ => {
+                                COUNTERS
+                                    .iter()
+                                    .find(|counter| counter.surface == surface)
+                                    .unwrap()
+                                    .reading
+                            }
+                        };
+                        return format!(
+                            "{}{}",
+                            decimal_reading(integer, decimal.as_str()),
+                            reading
+                        );
+                    }
+                    match surface {
+                        "日" => day_count_reading(integer),
+                        "月" if (1..=12).contains(&integer) => month_reading(integer),
+                        "月" => caps[0].to_string(),
+                        "つ" => tsu_reading(integer).unwrap_or_else(|| caps[0].to_string()),
+                        _ => COUNTERS
+                            .iter()
+                            .find(|counter| counter.surface == surface)
+                            .unwrap()
+                            .read(integer),
+                    }
+                })
+                .into_owned();
+        }
+        if options.acronyms {
+            text = self
+                .latin
+                .replace_all(&text, |caps: &Captures| {
+                    let word = &caps[0];
+                    if word.len() >= 2 && word.chars().all(|c| c.is_ascii_uppercase()) {
+                        spell_out(word)
+                    } else {
+                        word.to_string()
+                    }
+                })
+                .into_owned();
+        }
+        if options.english {
+            text = self.english.transliterate_text(&text);
+        }
+        if options.emoji {
+            text = self.replace_emoji(&text);
+        }
+
+        text
+    }
+
+    fn replace_emoji(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if !is_emoji(c) {
+                result.push(c);
+                continue;
+            }
+            // ZWJで繋がった絵文字や肌の色の修飾子は1つの絵文字として扱う。
+            let mut emoji = c.to_string();
+            while let Some(&next) = chars.peek() {
+                if next == '\u{200d}' {
+                    emoji.push(next);
+                    chars.next();
+                    if let Some(joined) = chars.next() {
+                        emoji.push(joined);
+                    }
+                } else if next == '\u{fe0f}' || ('\u{1f3fb}'..='\u{1f3ff}').contains(&next) {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if let Some(reading) = self
+                .emoji
+                .get(&emoji)
+                .or_else(|| self.emoji.get(&c.to_string()))
+            {
+                result.push_str(reading);
+            }
+        }
+
+        result
+    }
+}
+
+impl Counter {
+    fn read(&self, number: u64) -> String {
+        if let Some((_, reading)) = self.special.iter().find(|(n, _)| *n == number) {
+            return reading.to_string();
+        }
+
+        let number_reading = number_reading(number);
+        if let Some(ending) = self
+            .geminate
+            .iter()
+            .find(|ending| number_reading.ends_with(*ending))
+        {
+            let stem = &number_reading[..number_reading.len() - ending.len()];
+            let geminated = match *ending {
+                "イチ" => "イッ",
+                "ロク" => "ロッ",
+                "ハチ" => "ハッ",
+                "ジュウ" => "ジュッ",
+                // ヒ・ビ・ピは残し、クだけを促音にする。
+                _ => "ャッ",
+            };
+            let reading = if self.handakuten_after_geminate {
+                handakuten(self.reading)
+            } else {
+                self.reading.to_string()
+            };
+            return format!("{}{}{}", stem, geminated, reading);
+        }
+        if self.yo && number_reading.ends_with("ヨン") {
+            return format!("{}{}", number_reading.trim_end_matches('ン'), self.reading);
+        }
+
+        let reading = match self.after_n {
+            AfterN::Voiced
+                if number_reading.ends_with('ン') && !number_reading.ends_with("ヨン") =>
+            {
+                dakuten(self.reading)
+            }
+            AfterN::Handakuten if number_reading.ends_with('ン') => handakuten(self.reading),
+            _ => self.reading.to_string(),
+        };
+        format!("{}{}", number_reading, reading)
+    }
+}
+
+fn replace_first_kana(reading: &str, table: &[(char, char)]) -> String {
+    let mut chars = reading.chars();
+    let first = chars.next().unwrap_or_default();
+    let first = table
+        .iter()
+        .find(|(from, _)| *from == first)
+        .map_or(first, |(_, to)| *to);
+    std::iter::once(first).chain(chars).collect()
+}
+
+fn dakuten(reading: &str) -> String {
+    replace_first_kana(
+        reading,
+        &[
+            ('カ', 'ガ'),
+            ('キ', 'ギ'),
+            ('ク', 'グ'),
+            ('ケ', 'ゲ'),
+            ('コ', 'ゴ'),
+            ('サ', 'ザ'),
+            ('シ', 'ジ'),
+            ('ス', 'ズ'),
+            ('セ', 'ゼ'),
+            ('ソ', 'ゾ'),
+            ('ハ', 'バ'),
+            ('ヒ', 'ビ'),
+            ('フ', 'ブ'),
+            ('ヘ', 'ベ'),
+            ('ホ', 'ボ'),
+        ],
+    )
+}
+
+fn handakuten(reading: &str) -> String {
+    replace_first_kana(
+        reading,
+        &[
+            ('ハ', 'パ'),
+            ('ヒ', 'ピ'),
+            ('フ', 'プ'),
+            ('ヘ', 'ペ'),
+            ('ホ', 'ポ'),
+        ],
+    )
+}
+
+fn parse_number(text: &str) -> Option<u64> {
+    text.replace(',', "").parse().ok()
+}
+
+const DIGITS: [&str; 10] = [
+    "ゼロ",
+    "イチ",
+    "ニ",
+    "サン",
+    "ヨン",
+    "ゴ",
+    "ロク",
+    "ナナ",
+    "ハチ",
+    "キュウ",
+];
+
+fn under_10000_reading(n: u64) -> String {
+    let mut reading = String::new();
+    let (thousands, hundreds, tens, ones) = (n / 1000, n / 100 % 10, n / 10 % 10, n % 10);
+    reading.push_str(match thousands {
+        0 => "",
+        1 => "セン",
+        3 => "サンゼン",
+        8 => "ハッセン",
+        _ => "",
+    });
+    if !matches!(thousands, 0 | 1 | 3 | 8) {
+        reading.push_str(DIGITS[thousands as usize]);
+        reading.push_str("セン");
+    }
+    reading.push_str(match hundreds {
+        0 => "",
+        1 => "ヒャク",
+        3 => "サンビャク",
+        6 => "ロッピャク",
+        8 => "ハッピャク",
+        _ => "",
+    });
+    if !matches!(hundreds, 0 | 1 | 3 | 6 | 8) {
+        reading.push_str(DIGITS[hundreds as usize]);
+        reading.push_str("ヒャク");
+    }
+    if tens > 1 {
+        reading.push_str(DIGITS[tens as usize]);
+    }
+    if tens > 0 {
+        reading.push_str("ジュウ");
+    }
+    if ones > 0 {
+        reading.push_str(DIGITS[ones as usize]);
+    }
+
+    reading
+}
+
+/// 数をカタカナの読みにする。
+pub fn number_reading(n: u64) -> String {
+    if n == 0 {
+        return DIGITS[0].to_string();
+    }
+
+    let mut reading = String::new();
+    let mut rest = n;
+    for (unit, name) in [
+        (10u64.pow(16), "ケイ"),
+        (10u64.pow(12), "チョウ"),
+        (10u64.pow(8), "オク"),
+        (10u64.pow(4), "マン"),
+    ] {
+        let group = rest / unit;
+        rest %= unit;
+        if group == 0 {
+            continue;
+        }
+        let mut group_reading = under_10000_reading(group);
+        if group_reading.starts_with("セン") {
+            group_reading.insert_str(0, "イッ");
+        }
+        if matches!(name, "チョウ" | "ケイ") {
+            for (ending, geminated) in [("イチ", "イッ"), ("ハチ", "ハッ"), ("ジュウ", "ジュッ")]
+            {
+                if let Some(stem) = group_reading.strip_suffix(ending) {
+                    group_reading = format!("{}{}", stem, geminated);
+                    break;
+                }
+            }
+        }
+        reading.push_str(&group_reading);
+        reading.push_str(name);
+    }
+    reading.push_str(&under_10000_reading(rest));
+
+    reading
+}
+
+/// 小数の読み。`decimal`は`.`から始まる小数部分。
+fn decimal_reading(integer: u64, decimal: &str) -> String {
+    let mut reading = number_reading(integer);
+    for (ending, geminated) in [("イチ", "イッ"), ("ハチ", "ハッ"), ("ジュウ", "ジュッ")]
+    {
+        if let Some(stem) = reading.strip_suffix(ending) {
+            reading = format!("{}{}", stem, geminated);
+            break;
+        }
+    }
+    reading.push_str("テン");
+    for digit in decimal.chars().filter_map(|c| c.to_digit(10)) {
+        reading.push_str(DIGITS[digit as usize]);
+    }
+
+    reading
+}
+
+fn month_reading(month: u64) -> String {
+    match month {
+        4 => "シガツ".to_string(),
+        7 => "シチガツ".to_string(),
+        9 => "クガツ".to_string(),
+        _ => format!("{}ガツ", number_reading(month)),
+    }
+}
+
+fn day_count_reading(day: u64) -> String {
+    match day {
+        1 => "イチニチ".to_string(),
+        2 => "フツカ".to_string(),
+        3 => "ミッカ".to_string(),
+        4 => "ヨッカ".to_string(),
+        5 => "イツカ".to_string(),
+        6 => "ムイカ".to_string(),
+        7 => "ナノカ".to_string(),
+        8 => "ヨウカ".to_string(),
+        9 => "ココノカ".to_string(),
+        10 => "トオカ".to_string(),
+        20 => "ハツカ".to_string(),
+        _ if day % 10 == 4 && day < 100 => format!("{}ヨッカ", number_reading(day - 4)),
+        _ => format!("{}ニチ", number_reading(day)),
+    }
+}
+
+fn day_of_month_reading(day: u64) -> String {
+    match day {
+        1 => "ツイタチ".to_string(),
+        _ => day_count_reading(day),
+    }
+}
+
+fn hour_reading(hour: u64) -> String {
+    match hour {
+        4 => "ヨジ".to_string(),
+        7 => "シチジ".to_string(),
+        9 => "クジ".to_string(),
+        _ if hour % 10 == 4 => format!("{}ヨジ", number_reading(hour - 4)),
+        _ if hour % 10 == 9 => format!("{}クジ", number_reading(hour - 9)),
+        _ => format!("{}ジ", number_reading(hour)),
+    }
+}
+
+fn tsu_reading(n: u64) -> Option<String> {
+    let reading = match n {
+        1 => "ヒトツ",
+        2 => "フタツ",
+        3 => "ミッツ",
+        4 => "ヨッツ",
+        5 => "イツツ",
+        6 => "ムッツ",
+        7 => "ナナツ",
+        8 => "ヤッツ",
+        9 => "ココノツ",
+        10 => "トオ",
+        _ => return None,
+    };
+    Some(reading.to_string())
+}
+
+/// アルファベットの読み。
+const LETTERS: [&str; 26] = [
+    "エー",
+    "ビー",
+    "シー",
+    "ディー",
+    "イー",
+    "エフ",
+    "ジー",
+    "エイチ",
+    "アイ",
+    "ジェー",
+    "ケー",
+    "エル",
+    "エム",
+    "エヌ",
+    "オー",
+    "ピー",
+    "キュー",
+    "アール",
+    "エス",
+    "ティー",
+    "ユー",
+    "ブイ",
+    "ダブリュー",
+    "エックス",
+    "ワイ",
+    "ゼット",
+];
+
+/// 英数字と記号を1文字ずつ読む。
+pub fn spell_out(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' => {
+                LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize].to_string()
+            }
+            '0'..='9' => DIGITS[c.to_digit(10).unwrap() as usize].to_string(),
+            '.' => "ドット".to_string(),
+            '@' => "アットマーク".to_string(),
+            '-' => "ハイフン".to_string(),
+            '_' => "アンダーバー".to_string(),
+            '+' => "プラス".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+pub(crate) fn to_half_width(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap(),
+            '\u{3000}' => ' ',
+            '￥' => '¥',
+            c => c,
+        })
+        .collect()
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c,
+        '\u{1f300}'..='\u{1faff}'
+        | '\u{2600}'..='\u{27bf}'
+        | '\u{2b50}'
+        | '\u{2b55}'
+        | '\u{1f1e6}'..='\u{1f1ff}')
+}
+
+impl Synthesizer {
+    /// テキストを正規化してからAudioQueryを生成する。
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - 音声合成するテキスト。
+    /// * `style_id` - 音声のスタイルID。
+    /// * `normalizer` - テキスト正規化器。
+    pub fn create_audio_query_normalized(
+        &self,
+        text: &str,
+        style_id: StyleId,
+        normalizer: &TextNormalizer,
+    ) -> Result<AudioQuery> {
+        self.create_audio_query(&normalizer.normalize(text), style_id)
+    }
+
+    /// テキストを正規化してから音声を合成する。
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - 音声合成するテキスト。
+    /// * `style_id` - 音声のスタイルID。
+    /// * `normalizer` - テキスト正規化器。
+    /// * `options` - 音声合成のオプション。
+    ///
+    /// # Returns
+    ///
+    /// WAV形式の音声データ。
+    pub fn tts_normalized(
+        &self,
+        text: &str,
+        style_id: StyleId,
+        normalizer: &TextNormalizer,
+        options: TtsOptions,
+    ) -> Result<Vec<u8>> {
+        self.tts(&normalizer.normalize(text), style_id, options)
+    }
+}
@@ -1,10 +1,12 @@
-use crate::{i32_to_result, Result};
+use crate::{asset::StagedPath, i32_to_result, AssetLoadError, Result};
 use std::{ffi::CString, mem::MaybeUninit, path::Path};
 use voicevox_core_sys as sys;
 
 /// テキスト解析器としてのOpen JTalk。
 pub struct OpenJtalkRc {
     pub(crate) inner: *mut sys::OpenJtalkRc,
+    /// [`OpenJtalkRc::from_files`]で書き出した一時ディレクトリ。`inner`より後に破棄する。
+    _staged: Option<StagedPath>,
 }
 
 impl OpenJtalkRc {
@@ -21,7 +23,23 @@ impl OpenJtalkRc {
             ptr.assume_init()
         };
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            _staged: None,
+        })
+    }
+
+    /// メモリ上の辞書ファイルからOpen JTalkを構築する。
+    ///
+    /// `files`は`(ファイル名, 内容)`の組で、`sys.dic`などの辞書ディレクトリの中のファイルをすべて含む。
+    /// コアは`use_user_dict`のときにも辞書ディレクトリを読みなおすので、一時ディレクトリに書き出したファ
+    /// イルは`OpenJtalkRc`を破棄するときに削除する。
+    pub fn from_files(files: &[(&str, &[u8])]) -> std::result::Result<Self, AssetLoadError> {
+        let staged = StagedPath::dir(files)?;
+        let mut open_jtalk = Self::new(staged.path())?;
+        open_jtalk._staged = Some(staged);
+
+        Ok(open_jtalk)
     }
 
     pub fn use_user_dict(&self, user_dict: &crate::UserDict) -> Result<()> {
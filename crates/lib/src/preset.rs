@@ -0,0 +1,268 @@
+use crate::{AudioQuery, StyleId};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// プリセットの読み書きのエラー。
+#[derive(Error, Debug)]
+pub enum PresetError {
+    /// ファイルの読み書きに失敗した
+    #[error("ファイルの読み書きに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// JSONの変換に失敗した
+    #[error("JSONの変換に失敗した")]
+    Json(#[from] serde_json::Error),
+
+    /// TOMLの読み込みに失敗した
+    #[error("TOMLの読み込みに失敗した")]
+    TomlDe(#[from] toml::de::Error),
+
+    /// TOMLの書き込みに失敗した
+    #[error("TOMLの書き込みに失敗した")]
+    TomlSer(#[from] toml::ser::Error),
+
+    /// 対応していない拡張子
+    #[error("対応していない拡張子：{0}")]
+    UnsupportedExtension(String),
+}
+
+/// 調整のプリセット。AudioQueryの全体の設定をまとめたもの。
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Preset {
+    /// プリセット名。
+    pub name: String,
+    /// プリセットを紐付けるスタイルID。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style_id: Option<StyleId>,
+    /// 全体の話速。
+    pub speed_scale: f32,
+    /// 全体の音高。
+    pub pitch_scale: f32,
+    /// 全体の抑揚。
+    pub intonation_scale: f32,
+    /// 全体の音量。
+    pub volume_scale: f32,
+    /// 音声の前の無音時間。
+    pub pre_phoneme_length: f32,
+    /// 音声の後の無音時間。
+    pub post_phoneme_length: f32,
+    /// 音声データの出力サンプリングレート。`None`の場合はAudioQueryの値をそのまま使う。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_sampling_rate: Option<u32>,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            style_id: None,
+            speed_scale: 1.0,
+            pitch_scale: 0.0,
+            intonation_scale: 1.0,
+            volume_scale: 1.0,
+            pre_phoneme_length: 0.1,
+            post_phoneme_length: 0.1,
+            output_sampling_rate: None,
+        }
+    }
+}
+
+impl Preset {
+    /// AudioQueryにプリセットを適用する。
+    pub fn apply(&self, audio_query: &mut AudioQuery) {
+        audio_query.speed_scale = self.speed_scale;
+        audio_query.pitch_scale = self.pitch_scale;
+        audio_query.intonation_scale = self.intonation_scale;
+        audio_query.volume_scale = self.volume_scale;
+        audio_query.pre_phoneme_length = self.pre_phoneme_length;
+        audio_query.post_phoneme_length = self.post_phoneme_length;
+        if let Some(output_sampling_rate) = self.output_sampling_rate {
+            audio_query.output_sampling_rate = output_sampling_rate;
+        }
+    }
+
+    /// AudioQueryの全体の設定からプリセットを作成する。
+    pub fn from_audio_query(name: &str, audio_query: &AudioQuery) -> Self {
+        Self {
+            name: name.to_string(),
+            style_id: None,
+            speed_scale: audio_query.speed_scale,
+            pitch_scale: audio_query.pitch_scale,
+            intonation_scale: audio_query.intonation_scale,
+            volume_scale: audio_query.volume_scale,
+            pre_phoneme_length: audio_query.pre_phoneme_length,
+            post_phoneme_length: audio_query.post_phoneme_length,
+            output_sampling_rate: Some(audio_query.output_sampling_rate),
+        }
+    }
+
+    /// JSONからプリセットを読み込む。
+    pub fn from_json(json: &str) -> std::result::Result<Self, PresetError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// プリセットをJSONに変換する。
+    pub fn to_json(&self) -> std::result::Result<String, PresetError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// TOMLからプリセットを読み込む。
+    pub fn from_toml(toml: &str) -> std::result::Result<Self, PresetError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// プリセットをTOMLに変換する。
+    pub fn to_toml(&self) -> std::result::Result<String, PresetError> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// プリセットをファイルから読み込む。形式は拡張子（`.json`か`.toml`）で判断する。
+    pub fn load<S: AsRef<Path>>(path: S) -> std::result::Result<Self, PresetError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match PresetFormat::from_path(path)? {
+            PresetFormat::Json => Self::from_json(&content),
+            PresetFormat::Toml => Self::from_toml(&content),
+        }
+    }
+
+    /// プリセットをファイルに保存する。形式は拡張子（`.json`か`.toml`）で判断する。
+    pub fn save<S: AsRef<Path>>(&self, path: S) -> std::result::Result<(), PresetError> {
+        let path = path.as_ref();
+        let content = match PresetFormat::from_path(path)? {
+            PresetFormat::Json => self.to_json()?,
+            PresetFormat::Toml => self.to_toml()?,
+        };
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// VOICEVOXエディタのプリセットを読み込む。
+    ///
+    /// エディタの設定ファイル全体、その`presets`、プリセットの配列、単体のプリセットのいずれも受け
+    /// 付ける。設定ファイル全体の場合は、デフォルトプリセットの設定からスタイルIDを紐付ける。
+    pub fn from_editor_json(json: &str) -> std::result::Result<Vec<Self>, PresetError> {
+        let presets = match serde_json::from_str(json)? {
+            EditorPresetsJson::Settings(settings) => {
+                let mut presets: IndexMap<String, Preset> = settings
+                    .presets
+                    .into_ordered()
+                    .map(|(key, preset)| (key, preset.into()))
+                    .collect();
+                for (voice_id, preset_key) in settings.default_preset_keys {
+                    // 音声IDは`{エンジンID}:{話者UUID}:{スタイルID}`の形式。
+                    let style_id = voice_id
+                        .rsplit(':')
+                        .next()
+                        .and_then(|style_id| style_id.parse().ok());
+                    if let Some(preset) = presets.get_mut(&preset_key) {
+                        preset.style_id = preset.style_id.or(style_id);
+                    }
+                }
+                presets.into_values().collect()
+            }
+            EditorPresetsJson::Presets(presets) => presets
+                .into_ordered()
+                .map(|(_, preset)| preset.into())
+                .collect(),
+            EditorPresetsJson::List(presets) => presets.into_iter().map(Into::into).collect(),
+            EditorPresetsJson::Single(preset) => vec![preset.into()],
+        };
+
+        Ok(presets)
+    }
+}
+
+enum PresetFormat {
+    Json,
+    Toml,
+}
+
+impl PresetFormat {
+    fn from_path(path: &Path) -> std::result::Result<Self, PresetError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "toml" => Ok(Self::Toml),
+            _ => Err(PresetError::UnsupportedExtension(extension.to_string())),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EditorPresetsJson {
+    Settings(EditorSettings),
+    Presets(EditorPresets),
+    List(Vec<EditorPreset>),
+    Single(EditorPreset),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EditorSettings {
+    presets: EditorPresets,
+    #[serde(default)]
+    default_preset_keys: IndexMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct EditorPresets {
+    items: IndexMap<String, EditorPreset>,
+    #[serde(default)]
+    keys: Vec<String>,
+}
+
+impl EditorPresets {
+    /// `keys`の順に並べる。`keys`に無いものは末尾に置く。
+    fn into_ordered(mut self) -> impl Iterator<Item = (String, EditorPreset)> {
+        let mut ordered: Vec<(String, EditorPreset)> = self
+            .keys
+            .iter()
+            .filter_map(|key| {
+                self.items
+                    .shift_remove(key)
+                    .map(|preset| (key.clone(), preset))
+            })
+            .collect();
+        ordered.extend(self.items);
+        ordered.into_iter()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EditorPreset {
+    name: String,
+    #[serde(default, alias = "style_id")]
+    style_id: Option<StyleId>,
+    speed_scale: f32,
+    pitch_scale: f32,
+    intonation_scale: f32,
+    volume_scale: f32,
+    pre_phoneme_length: f32,
+    post_phoneme_length: f32,
+}
+
+impl From<EditorPreset> for Preset {
+    fn from(preset: EditorPreset) -> Self {
+        Self {
+            name: preset.name,
+            style_id: preset.style_id,
+            speed_scale: preset.speed_scale,
+            pitch_scale: preset.pitch_scale,
+            intonation_scale: preset.intonation_scale,
+            volume_scale: preset.volume_scale,
+            pre_phoneme_length: preset.pre_phoneme_length,
+            post_phoneme_length: preset.post_phoneme_length,
+            output_sampling_rate: None,
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use crate::{
+    AccentPhrase, AudioQuery, MoraModel, SpeakerMeta, StyleId, SynthesisOptions, Synthesizer,
+    VoicevoxError,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// VOICEVOX ENGINEのエンジンID。
+pub const DEFAULT_ENGINE_ID: &str = "074fc39e-678b-4c13-8916-ffca8d505d1d";
+
+/// 書き出すプロジェクトファイルのバージョン。
+///
+/// エディタは0.17.0未満のファイルを`audioItems`が最上位にある古い形式として移行するので、`talk`と
+/// `song`に分かれた形式を導入した0.17.0にする。`song`も0.17.0の形式で書き、それより後の変更は
+/// エディタの移行に任せる。
+const PROJECT_APP_VERSION: &str = "0.17.0";
+
+/// プロジェクトファイルの読み書きのエラー。
+#[derive(Error, Debug)]
+pub enum ProjectError {
+    /// ファイルの読み書きに失敗した
+    #[error("ファイルの読み書きに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// プロジェクトファイルの形式が不正
+    #[error("プロジェクトファイルの形式が不正")]
+    Json(#[from] serde_json::Error),
+
+    /// 音声アイテムに話者の指定が無い
+    #[error("音声アイテム`{0}`に話者の指定が無い")]
+    MissingVoice(String),
+
+    /// 音声合成に失敗した
+    #[error("音声合成に失敗した")]
+    Voicevox(#[from] VoicevoxError),
+}
+
+/// プロジェクトファイルの音声アイテム。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectAudioItem {
+    /// テキスト。
+    pub text: String,
+    /// エンジンID。
+    pub engine_id: String,
+    /// 話者のUUID。
+    pub speaker_id: Option<String>,
+    /// スタイルID。
+    pub style_id: StyleId,
+    /// AudioQuery。`None`の場合は合成時に`text`から生成する。
+    pub audio_query: Option<AudioQuery>,
+}
+
+impl ProjectAudioItem {
+    /// 音声アイテムを作成する。エンジンIDは[`DEFAULT_ENGINE_ID`]となる。
+    pub fn new(text: &str, style_id: StyleId, audio_query: Option<AudioQuery>) -> Self {
+        Self {
+            text: text.to_string(),
+            engine_id: DEFAULT_ENGINE_ID.to_string(),
+            speaker_id: None,
+            style_id,
+            audio_query,
+        }
+    }
+}
+
+/// VOICEVOXエディタのプロジェクトファイル（`.vvproj`）のトーク部分。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Project {
+    /// 音声アイテム。エディタでの並び順。
+    pub audio_items: Vec<ProjectAudioItem>,
+}
+
+impl Project {
+    /// 音声アイテムからプロジェクトを作成する。
+    pub fn new<I: IntoIterator<Item = ProjectAudioItem>>(audio_items: I) -> Self {
+        Self {
+            audio_items: audio_items.into_iter().collect(),
+        }
+    }
+
+    /// プロジェクトファイルを読み込む。
+    ///
+    /// 0.17以降の`talk`と`song`に分かれた形式と、それ以前の`audioItems`が最上位にある形式に対応する。
+    /// `song`は読み飛ばす。
+    pub fn from_json(json: &str) -> std::result::Result<Self, ProjectError> {
+        let project: ProjectJson = serde_json::from_str(json)?;
+        let talk = match project {
+            ProjectJson::Current { talk } => talk,
+            ProjectJson::Legacy(talk) => talk,
+        };
+
+        let audio_items = talk
+            .into_ordered()
+            .map(|(key, item)| {
+                let (engine_id, speaker_id, style_id) = match item.voice {
+                    Some(voice) => (voice.engine_id, Some(voice.speaker_id), voice.style_id),
+                    None => (
+                        item.engine_id
+                            .unwrap_or_else(|| DEFAULT_ENGINE_ID.to_string()),
+                        None,
+                        item.style_id.ok_or(ProjectError::MissingVoice(key))?,
+                    ),
+                };
+                Ok(ProjectAudioItem {
+                    text: item.text,
+                    engine_id,
+                    speaker_id,
+                    style_id,
+                    audio_query: item.query.map(Into::into),
+                })
+            })
+            .collect::<std::result::Result<_, ProjectError>>()?;
+
+        Ok(Self { audio_items })
+    }
+
+    /// プロジェクトファイルをファイルから読み込む。
+    pub fn load<S: AsRef<Path>>(path: S) -> std::result::Result<Self, ProjectError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// エディタで開ける形式のプロジェクトファイルに変換する。
+    ///
+    /// 話者のUUIDが無い音声アイテムがある場合はエラーとなる。[`Project::assign_speakers`]で補う
+    /// こと。
+    pub fn to_json(&self) -> std::result::Result<String, ProjectError> {
+        let mut audio_keys = vec![];
+        let mut audio_items = IndexMap::new();
+        for (index, item) in self.audio_items.iter().enumerate() {
+            let key = uuid::Uuid::new_v4().to_string();
+            let speaker_id = item
+                .speaker_id
+                .clone()
+                .ok_or_else(|| ProjectError::MissingVoice(index.to_string()))?;
+            audio_items.insert(
+                key.clone(),
+                AudioItemJson {
+                    text: item.text.clone(),
+                    voice: Some(VoiceJson {
+                        engine_id: item.engine_id.clone(),
+                        speaker_id,
+                        style_id: item.style_id,
+                    }),
+                    engine_id: None,
+                    style_id: None,
+                    query: item.audio_query.clone().map(Into::into),
+                },
+            );
+            audio_keys.push(key);
+        }
+
+        let project = serde_json::json!({
+            "appVersion": PROJECT_APP_VERSION,
+            "talk": {
+                "audioKeys": audio_keys,
+                "audioItems": audio_items,
+            },
+            // エディタが0.17.0未満のファイルを移行するときに作る、空の`song`と同じもの。
+            "song": {
+                "tpqn": 480,
+                "tempos": [{ "position": 0, "bpm": 120 }],
+                "timeSignatures": [{ "measureNumber": 1, "beats": 4, "beatType": 4 }],
+                "tracks": [{
+                    "keyRangeAdjustment": 0,
+                    "notes": [],
+                }],
+            },
+        });
+
+        Ok(serde_json::to_string(&project)?)
+    }
+
+    /// エディタで開ける形式でプロジェクトファイルを保存する。
+    pub fn save<S: AsRef<Path>>(&self, path: S) -> std::result::Result<(), ProjectError> {
+        std::fs::write(path, self.to_json()?)?;
+
+        Ok(())
+    }
+
+    /// 話者のUUIDが無い音声アイテムに、スタイルIDから話者のUUIDを割り当てる。
+    pub fn assign_speakers(&mut self, metas: &[SpeakerMeta]) {
+        for item in &mut self.audio_items {
+            if item.speaker_id.is_some() {
+                continue;
+            }
+            item.speaker_id = metas
+                .iter()
+                .find(|meta| {
+                    meta.styles()
+                        .iter()
+                        .any(|style| style.id() == item.style_id)
+                })
+                .map(|meta| meta.speaker_uuid().to_string());
+        }
+    }
+}
+
+impl Synthesizer {
+    /// プロジェクトの音声アイテムを順に合成する。
+    ///
+    /// AudioQueryが無い音声アイテムは、テキストからAudioQueryを生成して合成する。
+    ///
+    /// # Returns
+    /// 音声アイテムごとのWAV形式の音声データ。
+    pub fn synthesis_project(
+        &self,
+        project: &Project,
+        options: SynthesisOptions,
+    ) -> std::result::Result<Vec<Vec<u8>>, ProjectError> {
+        project
+            .audio_items
+            .iter()
+            .map(|item| {
+                let wav = match &item.audio_query {
+                    Some(audio_query) => self.synthesis(audio_query, item.style_id, options)?,
+                    None => {
+                        let audio_query = self.create_audio_query(&item.text, item.style_id)?;
+                        self.synthesis(&audio_query, item.style_id, options)?
+                    }
+                };
+                Ok(wav)
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProjectJson {
+    Current { talk: TalkJson },
+    Legacy(TalkJson),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TalkJson {
+    #[serde(default)]
+    audio_keys: Vec<String>,
+    audio_items: IndexMap<String, AudioItemJson>,
+}
+
+impl TalkJson {
+    /// `audioKeys`の順に並べる。`audioKeys`に無いものは末尾に置く。
+    fn into_ordered(mut self) -> impl Iterator<Item = (String, AudioItemJson)> {
+        let mut ordered: Vec<(String, AudioItemJson)> = self
+            .audio_keys
+            .iter()
+            .filter_map(|key| {
+                self.audio_items
+                    .shift_remove(key)
+                    .map(|item| (key.clone(), item))
+            })
+            .collect();
+        ordered.extend(self.audio_items);
+        ordered.into_iter()
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioItemJson {
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    voice: Option<VoiceJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    engine_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    style_id: Option<StyleId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    query: Option<AudioQueryJson>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VoiceJson {
+    engine_id: String,
+    speaker_id: String,
+    style_id: StyleId,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioQueryJson {
+    accent_phrases: Vec<AccentPhraseJson>,
+    speed_scale: f32,
+    pitch_scale: f32,
+    intonation_scale: f32,
+    volume_scale: f32,
+    pre_phoneme_length: f32,
+    post_phoneme_length: f32,
+    output_sampling_rate: u32,
+    output_stereo: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kana: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccentPhraseJson {
+    moras: Vec<MoraJson>,
+    accent: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pause_mora: Option<MoraJson>,
+    #[serde(default)]
+    is_interrogative: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoraJson {
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    consonant: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    consonant_length: Option<f32>,
+    vowel: String,
+    vowel_length: f32,
+    pitch: f32,
+}
+
+impl From<AudioQueryJson> for AudioQuery {
+    fn from(query: AudioQueryJson) -> Self {
+        Self {
+            accent_phrases: query.accent_phrases.into_iter().map(Into::into).collect(),
+            speed_scale: query.speed_scale,
+            pitch_scale: query.pitch_scale,
+            intonation_scale: query.intonation_scale,
+            volume_scale: query.volume_scale,
+            pre_phoneme_length: query.pre_phoneme_length,
+            post_phoneme_length: query.post_phoneme_length,
+            output_sampling_rate: query.output_sampling_rate,
+            output_stereo: query.output_stereo,
+            kana: query.kana,
+        }
+    }
+}
+
+impl From<AudioQuery> for AudioQueryJson {
+    fn from(query: AudioQuery) -> Self {
+        Self {
+            accent_phrases: query.accent_phrases.into_iter().map(Into::into).collect(),
+            speed_scale: query.speed_scale,
+            pitch_scale: query.pitch_scale,
+            intonation_scale: query.intonation_scale,
+            volume_scale: query.volume_scale,
+            pre_phoneme_length: query.pre_phoneme_length,
+            post_phoneme_length: query.post_phoneme_length,
+            output_sampling_rate: query.output_sampling_rate,
+            output_stereo: query.output_stereo,
+            kana: query.kana,
+        }
+    }
+}
+
+impl From<AccentPhraseJson> for AccentPhrase {
+    fn from(accent_phrase: AccentPhraseJson) -> Self {
+        Self {
+            moras: accent_phrase.moras.into_iter().map(Into::into).collect(),
+            accent: accent_phrase.accent,
+            pause_mora: accent_phrase.pause_mora.map(Into::into),
+            is_interrogative: accent_phrase.is_interrogative,
+        }
+    }
+}
+
+impl From<AccentPhrase> for AccentPhraseJson {
+    fn from(accent_phrase: AccentPhrase) -> Self {
+        Self {
+            moras: accent_phrase.moras.into_iter().map(Into::into).collect(),
+            accent: accent_phrase.accent,
+            pause_mora: accent_phrase.pause_mora.map(Into::into),
+            is_interrogative: accent_phrase.is_interrogative,
+        }
+    }
+}
+
+impl From<MoraJson> for MoraModel {
+    fn from(mora: MoraJson) -> Self {
+        Self {
+            text: mora.text,
+            consonant: mora.consonant,
+            consonant_length: mora.consonant_length,
+            vowel: mora.vowel,
+            vowel_length: mora.vowel_length,
+            pitch: mora.pitch,
+        }
+    }
+}
+
+impl From<MoraModel> for MoraJson {
+    fn from(mora: MoraModel) -> Self {
+        Self {
+            text: mora.text,
+            consonant: mora.consonant,
+            consonant_length: mora.consonant_length,
+            vowel: mora.vowel,
+            vowel_length: mora.vowel_length,
+            pitch: mora.pitch,
+        }
+    }
+}
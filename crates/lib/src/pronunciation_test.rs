@@ -0,0 +1,158 @@
+use crate::{Result, StyleId, Synthesizer};
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::Path};
+use thiserror::Error;
+
+/// 読みのテストの読み込みのエラー。
+#[derive(Error, Debug)]
+pub enum PronunciationSuiteError {
+    /// ファイルの読み込みに失敗した
+    #[error("ファイルの読み込みに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// YAMLの読み込みに失敗した
+    #[error("YAMLの読み込みに失敗した：{0}")]
+    Yaml(#[from] serde_norway::Error),
+
+    /// TOMLの読み込みに失敗した
+    #[error("TOMLの読み込みに失敗した：{0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// 対応していない拡張子
+    #[error("対応していない拡張子：{0}")]
+    UnsupportedExtension(String),
+}
+
+/// 読みのテストのケース。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PronunciationCase {
+    /// 解析するテキスト。
+    pub text: String,
+    /// 期待するAquesTalk風記法のカナ。
+    pub kana: String,
+}
+
+/// 読みのテスト。テキストと期待するカナの組を並べたもの。
+///
+/// YAMLでは次のように書く。TOMLでは`[[cases]]`の配列にする。
+///
+/// ```yaml
+/// cases:
+///   - text: こんにちは
+///     kana: コンニチワ'
+/// ```
+///
+/// カナは`AudioQuery::kana`と同じ記法で書く。長音は`ー`ではなく母音で書くので、確かめたいテキストの
+/// `AudioQuery::kana`を元にするとよい。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PronunciationSuite {
+    /// ケース。
+    #[serde(default)]
+    pub cases: Vec<PronunciationCase>,
+}
+
+impl PronunciationSuite {
+    /// YAMLから読み込む。
+    pub fn from_yaml(yaml: &str) -> std::result::Result<Self, PronunciationSuiteError> {
+        Ok(serde_norway::from_str(yaml)?)
+    }
+
+    /// TOMLから読み込む。
+    pub fn from_toml(toml: &str) -> std::result::Result<Self, PronunciationSuiteError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// ファイルから読み込む。形式は拡張子（`.yaml`、`.yml`か`.toml`）で判断する。
+    pub fn load<S: AsRef<Path>>(path: S) -> std::result::Result<Self, PronunciationSuiteError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Self::from_yaml(&std::fs::read_to_string(path)?),
+            "toml" => Self::from_toml(&std::fs::read_to_string(path)?),
+            _ => Err(PronunciationSuiteError::UnsupportedExtension(extension)),
+        }
+    }
+
+    /// すべてのケースのテキストからAudioQueryを生成し、カナを期待する値と比べる。
+    ///
+    /// ユーザー辞書を使う場合は、先に`OpenJtalkRc::use_user_dict`で適用しておく。
+    pub fn run(&self, synthesizer: &Synthesizer, style_id: StyleId) -> Result<PronunciationReport> {
+        let mut results = Vec::with_capacity(self.cases.len());
+        for case in &self.cases {
+            let actual = synthesizer
+                .create_audio_query(&case.text, style_id)?
+                .kana
+                .unwrap_or_default();
+            results.push(PronunciationResult {
+                case: case.clone(),
+                actual,
+            });
+        }
+
+        Ok(PronunciationReport { results })
+    }
+}
+
+/// ケースの結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PronunciationResult {
+    /// ケース。
+    pub case: PronunciationCase,
+    /// 実際に生成されたカナ。
+    pub actual: String,
+}
+
+impl PronunciationResult {
+    /// 期待する値と一致したかどうか。
+    pub fn passed(&self) -> bool {
+        self.case.kana == self.actual
+    }
+}
+
+/// [`PronunciationSuite::run`]の結果。
+///
+/// `Display`で、一致しなかったケースを差分の形式で書き出す。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PronunciationReport {
+    /// ケースごとの結果。ケースの順に並ぶ。
+    pub results: Vec<PronunciationResult>,
+}
+
+impl PronunciationReport {
+    /// 一致しなかったケース。
+    pub fn failures(&self) -> impl Iterator<Item = &PronunciationResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+
+    /// すべてのケースが一致したかどうか。
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+impl fmt::Display for PronunciationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in self.failures() {
+            writeln!(f, "{}", result.case.text)?;
+            writeln!(f, "- {}", result.case.kana)?;
+            writeln!(f, "+ {}", result.actual)?;
+            // アクセント句ごとに比べて、違う句を示す。
+            let expected: Vec<&str> = result.case.kana.split(['/', '、']).collect();
+            let actual: Vec<&str> = result.actual.split(['/', '、']).collect();
+            for i in 0..expected.len().max(actual.len()) {
+                let expected = expected.get(i).copied().unwrap_or_default();
+                let actual = actual.get(i).copied().unwrap_or_default();
+                if expected != actual {
+                    writeln!(f, "  {}番目のアクセント句：{expected} → {actual}", i + 1)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        let passed = self.results.iter().filter(|result| result.passed()).count();
+        write!(f, "{passed}/{}件一致", self.results.len())
+    }
+}
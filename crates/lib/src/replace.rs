@@ -0,0 +1,372 @@
+use crate::{AudioQuery, Result, StyleId, Synthesizer, TtsOptions};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use thiserror::Error;
+
+/// 置換辞書の読み書きのエラー。
+#[derive(Error, Debug)]
+pub enum ReplaceDictError {
+    /// ファイルの読み書きに失敗した
+    #[error("ファイルの読み書きに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// JSONの変換に失敗した
+    #[error("JSONの変換に失敗した")]
+    Json(#[from] serde_json::Error),
+
+    /// TOMLの読み込みに失敗した
+    #[error("TOMLの読み込みに失敗した")]
+    TomlDe(#[from] toml::de::Error),
+
+    /// TOMLの書き込みに失敗した
+    #[error("TOMLの書き込みに失敗した")]
+    TomlSer(#[from] toml::ser::Error),
+
+    /// 正規表現が不正
+    #[error("正規表現が不正：{0}")]
+    Regex(#[from] regex::Error),
+
+    /// テキスト形式の行が不正
+    #[error("{line}行目が不正")]
+    InvalidLine {
+        /// 1から始まる行番号。
+        line: usize,
+    },
+
+    /// 対応していない拡張子
+    #[error("対応していない拡張子：{0}")]
+    UnsupportedExtension(String),
+}
+
+/// 置換のルール。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReplaceRule {
+    /// 置換する文字列、または正規表現。
+    pub pattern: String,
+    /// 置換後の文字列。正規表現の場合は`$1`などでキャプチャを参照できる。
+    pub replacement: String,
+    /// `pattern`が正規表現かどうか。
+    #[serde(default)]
+    pub regex: bool,
+    /// 優先度。大きいものから順に適用し、同じ優先度のものは追加した順に適用する。
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl ReplaceRule {
+    /// 文字列をそのまま置換するルールを作成する。
+    pub fn literal(pattern: &str, replacement: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            regex: false,
+            priority: 0,
+        }
+    }
+
+    /// 正規表現で置換するルールを作成する。
+    pub fn regex(pattern: &str, replacement: &str) -> Self {
+        Self {
+            regex: true,
+            ..Self::literal(pattern, replacement)
+        }
+    }
+
+    /// 優先度を設定する。
+    pub fn with_priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: ReplaceRule,
+    regex: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn new(rule: ReplaceRule) -> std::result::Result<Self, ReplaceDictError> {
+        let regex = if rule.regex {
+            Some(Regex::new(&rule.pattern)?)
+        } else {
+            None
+        };
+
+        Ok(Self { rule, regex })
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct ReplaceDictFile {
+    #[serde(default)]
+    rules: Vec<ReplaceRule>,
+}
+
+enum ReplaceDictFormat {
+    Json,
+    Toml,
+    Text,
+}
+
+impl ReplaceDictFormat {
+    fn from_path(path: &Path) -> std::result::Result<Self, ReplaceDictError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Ok(Self::Json),
+            "toml" => Ok(Self::Toml),
+            "txt" | "dic" => Ok(Self::Text),
+            _ => Err(ReplaceDictError::UnsupportedExtension(extension)),
+        }
+    }
+}
+
+/// 形態素解析の前にテキストへ適用する置換辞書。
+///
+/// ユーザー辞書と違い、`OpenJtalkRc::use_user_dict`を呼ばずに変更が反映される。
+#[derive(Debug, Clone, Default)]
+pub struct ReplaceDict {
+    rules: Vec<CompiledRule>,
+    source: Option<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ReplaceDict {
+    /// 空の置換辞書を作成する。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ルールの数。
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// ルールが無いかどうか。
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// ルールを適用する順に返す。
+    pub fn rules(&self) -> impl Iterator<Item = &ReplaceRule> {
+        self.rules.iter().map(|compiled| &compiled.rule)
+    }
+
+    /// ルールを追加する。
+    pub fn add_rule(&mut self, rule: ReplaceRule) -> std::result::Result<(), ReplaceDictError> {
+        self.insert(CompiledRule::new(rule)?);
+
+        Ok(())
+    }
+
+    fn insert(&mut self, compiled: CompiledRule) {
+        let index = self
+            .rules
+            .iter()
+            .position(|other| other.rule.priority < compiled.rule.priority)
+            .unwrap_or(self.rules.len());
+        self.rules.insert(index, compiled);
+    }
+
+    /// `rules`で返される順番で`index`番目のルールを削除する。
+    ///
+    /// # Panics
+    ///
+    /// `index`が範囲外の場合。
+    pub fn remove_rule(&mut self, index: usize) -> ReplaceRule {
+        self.rules.remove(index).rule
+    }
+
+    /// すべてのルールを削除する。
+    pub fn clear(&mut self) {
+        self.rules.clear();
+    }
+
+    /// テキストにルールを順に適用する。
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for compiled in &self.rules {
+            text = match &compiled.regex {
+                Some(regex) => regex
+                    .replace_all(&text, compiled.rule.replacement.as_str())
+                    .into_owned(),
+                None if compiled.rule.pattern.is_empty() => text,
+                None => text.replace(&compiled.rule.pattern, &compiled.rule.replacement),
+            };
+        }
+
+        text
+    }
+
+    /// 他の置換辞書のルールを追加する。
+    pub fn import(&mut self, other: &ReplaceDict) {
+        for compiled in &other.rules {
+            self.insert(compiled.clone());
+        }
+    }
+
+    fn from_rules(rules: Vec<ReplaceRule>) -> std::result::Result<Self, ReplaceDictError> {
+        let mut dict = Self::new();
+        for rule in rules {
+            dict.add_rule(rule)?;
+        }
+
+        Ok(dict)
+    }
+
+    fn to_file(&self) -> ReplaceDictFile {
+        ReplaceDictFile {
+            rules: self.rules().cloned().collect(),
+        }
+    }
+
+    /// JSONから置換辞書を読み込む。
+    pub fn from_json(json: &str) -> std::result::Result<Self, ReplaceDictError> {
+        let file: ReplaceDictFile = serde_json::from_str(json)?;
+        Self::from_rules(file.rules)
+    }
+
+    /// 置換辞書をJSONに変換する。
+    pub fn to_json(&self) -> std::result::Result<String, ReplaceDictError> {
+        Ok(serde_json::to_string_pretty(&self.to_file())?)
+    }
+
+    /// TOMLから置換辞書を読み込む。
+    pub fn from_toml(toml: &str) -> std::result::Result<Self, ReplaceDictError> {
+        let file: ReplaceDictFile = toml::from_str(toml)?;
+        Self::from_rules(file.rules)
+    }
+
+    /// 置換辞書をTOMLに変換する。
+    pub fn to_toml(&self) -> std::result::Result<String, ReplaceDictError> {
+        Ok(toml::to_string_pretty(&self.to_file())?)
+    }
+
+    /// 棒読みちゃんの置換辞書のような、`置換する文字列<TAB>置換後の文字列`の行からなるテキストを読み込む。
+    ///
+    /// 空行と`#`から始まる行は無視する。すべて文字列をそのまま置換するルールになり、優先度は0になる。
+    pub fn from_text(text: &str) -> std::result::Result<Self, ReplaceDictError> {
+        let mut dict = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (pattern, replacement) = line
+                .split_once('\t')
+                .ok_or(ReplaceDictError::InvalidLine { line: index + 1 })?;
+            dict.add_rule(ReplaceRule::literal(pattern, replacement))?;
+        }
+
+        Ok(dict)
+    }
+
+    /// 置換辞書をテキスト形式に変換する。正規表現のルールは含めない。
+    pub fn to_text(&self) -> String {
+        self.rules()
+            .filter(|rule| !rule.regex)
+            .map(|rule| format!("{}\t{}\n", rule.pattern, rule.replacement))
+            .collect()
+    }
+
+    /// 置換辞書をファイルから読み込む。形式は拡張子（`.json`、`.toml`、`.txt`か`.dic`）で判断する。
+    ///
+    /// 読み込んだファイルは[`ReplaceDict::reload_if_changed`]で再読み込みできる。
+    pub fn load<S: AsRef<Path>>(path: S) -> std::result::Result<Self, ReplaceDictError> {
+        let path = path.as_ref();
+        let modified = std::fs::metadata(path)?.modified().ok();
+        let mut dict = Self::read(path)?;
+        dict.source = Some((path.to_path_buf(), modified));
+
+        Ok(dict)
+    }
+
+    fn read(path: &Path) -> std::result::Result<Self, ReplaceDictError> {
+        let format = ReplaceDictFormat::from_path(path)?;
+        let content = std::fs::read_to_string(path)?;
+        match format {
+            ReplaceDictFormat::Json => Self::from_json(&content),
+            ReplaceDictFormat::Toml => Self::from_toml(&content),
+            ReplaceDictFormat::Text => Self::from_text(&content),
+        }
+    }
+
+    /// 置換辞書をファイルに保存する。形式は拡張子（`.json`、`.toml`、`.txt`か`.dic`）で判断する。
+    pub fn save<S: AsRef<Path>>(&self, path: S) -> std::result::Result<(), ReplaceDictError> {
+        let path = path.as_ref();
+        let content = match ReplaceDictFormat::from_path(path)? {
+            ReplaceDictFormat::Json => self.to_json()?,
+            ReplaceDictFormat::Toml => self.to_toml()?,
+            ReplaceDictFormat::Text => self.to_text(),
+        };
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// [`ReplaceDict::load`]で読み込んだファイルが更新されていれば読み込みなおす。
+    ///
+    /// 読み込みに失敗した場合は今のルールをそのまま残す。
+    ///
+    /// # Returns
+    ///
+    /// 読み込みなおした場合は`true`。
+    pub fn reload_if_changed(&mut self) -> std::result::Result<bool, ReplaceDictError> {
+        let Some((path, modified)) = &self.source else {
+            return Ok(false);
+        };
+        let current = std::fs::metadata(path)?.modified().ok();
+        if current.is_some() && current == *modified {
+            return Ok(false);
+        }
+
+        let dict = Self::read(path)?;
+        self.rules = dict.rules;
+        self.source = Some((path.clone(), current));
+
+        Ok(true)
+    }
+}
+
+impl Synthesizer {
+    /// 置換辞書を適用してからAudioQueryを生成する。
+    ///
+    /// # Arguments
+    /// * `text` - 日本語テキスト。
+    /// * `style_id` - 音声のスタイルID。
+    /// * `replace_dict` - 置換辞書。
+    pub fn create_audio_query_with_replace_dict(
+        &self,
+        text: &str,
+        style_id: StyleId,
+        replace_dict: &ReplaceDict,
+    ) -> Result<AudioQuery> {
+        self.create_audio_query(&replace_dict.apply(text), style_id)
+    }
+
+    /// 置換辞書を適用してから音声を合成する。
+    ///
+    /// # Arguments
+    /// * `text` - 日本語テキスト。
+    /// * `style_id` - 音声のスタイルID。
+    /// * `replace_dict` - 置換辞書。
+    /// * `options` - 音声合成のオプション。
+    ///
+    /// # Returns
+    /// WAV形式の音声データ。
+    pub fn tts_with_replace_dict(
+        &self,
+        text: &str,
+        style_id: StyleId,
+        replace_dict: &ReplaceDict,
+        options: TtsOptions,
+    ) -> Result<Vec<u8>> {
+        self.tts(&replace_dict.apply(text), style_id, options)
+    }
+}
@@ -0,0 +1,288 @@
+use crate::normalize::URL_PATTERN;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// 要素の置き換え方。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplacePolicy {
+    /// そのままにする。
+    Keep,
+    /// 取り除く。
+    Remove,
+    /// 指定した文字列に置き換える。
+    Replace(String),
+}
+
+impl ReplacePolicy {
+    fn apply(&self, original: &str) -> String {
+        match self {
+            ReplacePolicy::Keep => original.to_string(),
+            ReplacePolicy::Remove => String::new(),
+            ReplacePolicy::Replace(text) => text.clone(),
+        }
+    }
+}
+
+/// コードブロックの扱い。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeBlockPolicy {
+    /// 中身をそのまま読む。
+    Keep,
+    /// 読まない。
+    Skip,
+    /// 「コード」・「rustのコード」のように要約する。
+    Summarize,
+}
+
+/// チャットのメッセージを読み上げ用のテキストにする規則。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizeOptions {
+    /// 名前がわからないユーザー・ロールへのメンション（`<@123>`・`<@&123>`）。
+    pub mentions: ReplacePolicy,
+    /// 名前がわからないチャンネル（`<#123>`）。
+    pub channels: ReplacePolicy,
+    /// URL。
+    pub urls: ReplacePolicy,
+    /// コードブロック（```` ``` ````で囲まれた部分）。
+    pub code_blocks: CodeBlockPolicy,
+    /// 読みが登録されていないカスタム絵文字（`<:name:123>`）。`Keep`の場合は絵文字の名前を読む。
+    pub custom_emoji: ReplacePolicy,
+    /// 繰り返しをまとめるかどうか（`wwwww`→`わらわら`、`ーーーー`→`ー`）。
+    pub collapse_repeats: bool,
+    /// `collapse_repeats`のとき、同じ文字が続いてよい最大の数。
+    pub max_repeat: usize,
+    /// 最大の文字数。超えた部分は「以下略」にする。
+    pub max_length: Option<usize>,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            mentions: ReplacePolicy::Replace("メンション".to_string()),
+            channels: ReplacePolicy::Replace("チャンネル".to_string()),
+            urls: ReplacePolicy::Replace("URL省略".to_string()),
+            code_blocks: CodeBlockPolicy::Summarize,
+            custom_emoji: ReplacePolicy::Keep,
+            collapse_repeats: true,
+            max_repeat: 3,
+            max_length: Some(100),
+        }
+    }
+}
+
+/// 長さの上限を超えたときに付ける文字列。
+const OMISSION: &str = "以下略";
+
+/// 標準の顔文字の読み。
+const KAOMOJI_READINGS: &[(&str, &str)] = &[
+    ("(´・ω・`)", "ショボーン"),
+    ("(´・ω・｀)", "ショボーン"),
+    ("(*´ω｀*)", "ニコニコ"),
+    ("(`・ω・´)", "キリッ"),
+    ("( ˘ω˘ )", "スヤァ"),
+    ("(・∀・)", "ニヤニヤ"),
+    ("(;_;)", "シクシク"),
+    ("(T_T)", "シクシク"),
+    ("(^_^;)", "アセアセ"),
+    ("(^_^)", "ニコニコ"),
+    ("(^^)", "ニコニコ"),
+    ("(^o^)", "ワーイ"),
+    ("\\(^o^)/", "バンザイ"),
+    ("m(_ _)m", "ペコリ"),
+    ("orz", "ガックリ"),
+];
+
+/// DiscordやTwitchのメッセージを、メンションやURLなどを読みやすく整えた読み上げ用のテキストにする。
+pub struct ChatSanitizer {
+    options: SanitizeOptions,
+    users: HashMap<String, String>,
+    channels: HashMap<String, String>,
+    custom_emoji: HashMap<String, String>,
+    kaomoji: Vec<(String, String)>,
+    code_block: Regex,
+    inline_code: Regex,
+    url: Regex,
+    mention: Regex,
+    channel: Regex,
+    plain_mention: Regex,
+    emoji: Regex,
+    laugh: Regex,
+}
+
+impl Default for ChatSanitizer {
+    fn default() -> Self {
+        Self::new(SanitizeOptions::default())
+    }
+}
+
+impl ChatSanitizer {
+    /// サニタイザーを作成する。
+    pub fn new(options: SanitizeOptions) -> Self {
+        let mut sanitizer = Self {
+            options,
+            users: HashMap::new(),
+            channels: HashMap::new(),
+            custom_emoji: HashMap::new(),
+            kaomoji: vec![],
+            code_block: Regex::new(r"(?s)```([\w+\-#]*)\n?(.*?)```").unwrap(),
+            inline_code: Regex::new(r"`([^`\n]+)`").unwrap(),
+            url: Regex::new(URL_PATTERN).unwrap(),
+            mention: Regex::new(r"<@[!&]?(\d+)>").unwrap(),
+            channel: Regex::new(r"<#(\d+)>").unwrap(),
+            plain_mention: Regex::new(r"(^|[^\w.])@(\w+)").unwrap(),
+            emoji: Regex::new(r"<a?:(\w+):\d+>").unwrap(),
+            laugh: Regex::new(r"(^|[^A-Za-zｗ])[wｗ]+($|[^A-Za-zｗ])").unwrap(),
+        };
+        for (kaomoji, reading) in KAOMOJI_READINGS {
+            sanitizer.add_kaomoji(kaomoji, reading);
+        }
+
+        sanitizer
+    }
+
+    /// 規則を返す。
+    pub fn options(&self) -> &SanitizeOptions {
+        &self.options
+    }
+
+    /// ユーザー・ロールのIDと読み上げる名前を登録する。
+    pub fn add_user(&mut self, id: &str, name: &str) {
+        self.users.insert(id.to_string(), name.to_string());
+    }
+
+    /// チャンネルのIDと読み上げる名前を登録する。
+    pub fn add_channel(&mut self, id: &str, name: &str) {
+        self.channels.insert(id.to_string(), name.to_string());
+    }
+
+    /// カスタム絵文字の名前と読みを登録する。
+    pub fn add_custom_emoji(&mut self, name: &str, reading: &str) {
+        self.custom_emoji
+            .insert(name.to_string(), reading.to_string());
+    }
+
+    /// 顔文字と読みを登録する。既にある場合は上書きする。
+    pub fn add_kaomoji(&mut self, kaomoji: &str, reading: &str) {
+        self.kaomoji.retain(|(k, _)| k != kaomoji);
+        self.kaomoji
+            .push((kaomoji.to_string(), reading.to_string()));
+        // 長いものから順に置き換える。
+        self.kaomoji
+            .sort_by_key(|(k, _)| std::cmp::Reverse(k.chars().count()));
+    }
+
+    /// メッセージを読み上げ用のテキストにする。
+    pub fn sanitize(&self, message: &str) -> String {
+        let options = &self.options;
+
+        let mut text = self
+            .code_block
+            .replace_all(message, |caps: &Captures| match options.code_blocks {
+                CodeBlockPolicy::Keep => caps[2].to_string(),
+                CodeBlockPolicy::Skip => String::new(),
+                CodeBlockPolicy::Summarize if caps[1].is_empty() => "コード".to_string(),
+                CodeBlockPolicy::Summarize => format!("{}のコード", &caps[1]),
+            })
+            .into_owned();
+        // 顔文字には`が含まれるものがあるので、インラインコードより先に置き換える。
+        for (kaomoji, reading) in &self.kaomoji {
+            text = text.replace(kaomoji, reading);
+        }
+        text = self.inline_code.replace_all(&text, "$1").into_owned();
+        text = self
+            .url
+            .replace_all(&text, |caps: &Captures| options.urls.apply(&caps[0]))
+            .into_owned();
+        text = self
+            .mention
+            .replace_all(&text, |caps: &Captures| match self.users.get(&caps[1]) {
+                Some(name) => name.clone(),
+                None => options.mentions.apply(&caps[0]),
+            })
+            .into_owned();
+        text = self
+            .channel
+            .replace_all(&text, |caps: &Captures| match self.channels.get(&caps[1]) {
+                Some(name) => name.clone(),
+                None => options.channels.apply(&caps[0]),
+            })
+            .into_owned();
+        // Twitchなどの`@name`は名前をそのまま読む。
+        text = self
+            .plain_mention
+            .replace_all(&text, |caps: &Captures| {
+                let name = self.users.get(&caps[2]).map_or(&caps[2], String::as_str);
+                format!("{}{}", &caps[1], name)
+            })
+            .into_owned();
+        text = self
+            .emoji
+            .replace_all(&text, |caps: &Captures| {
+                match self.custom_emoji.get(&caps[1]) {
+                    Some(reading) => reading.clone(),
+                    None => options.custom_emoji.apply(&caps[1]),
+                }
+            })
+            .into_owned();
+        if options.collapse_repeats {
+            text = self.collapse_repeats(&text);
+        }
+
+        let text = text.trim();
+        match options.max_length {
+            Some(max_length) if text.chars().count() > max_length => {
+                let mut text: String = text.chars().take(max_length).collect();
+                text.push_str(OMISSION);
+                text
+            }
+            _ => text.to_string(),
+        }
+    }
+
+    fn collapse_repeats(&self, text: &str) -> String {
+        // `replace_all`は重なった一致を見つけないので、前後の文字を残して繰り返す。
+        let mut text = text.to_string();
+        loop {
+            let replaced = self
+                .laugh
+                .replace_all(&text, |caps: &Captures| {
+                    let laugh = caps[0]
+                        .trim_start_matches(|c: char| c != 'w' && c != 'ｗ')
+                        .trim_end_matches(|c: char| c != 'w' && c != 'ｗ');
+                    let reading = if laugh.chars().count() == 1 {
+                        "わら"
+                    } else {
+                        "わらわら"
+                    };
+                    format!("{}{}{}", &caps[1], reading, &caps[2])
+                })
+                .into_owned();
+            if replaced == text {
+                break;
+            }
+            text = replaced;
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let mut previous = None;
+        let mut count = 0;
+        for c in text.chars() {
+            if Some(c) == previous {
+                count += 1;
+            } else {
+                previous = Some(c);
+                count = 1;
+            }
+            let max_repeat = if matches!(c, 'ー' | '〜' | '~') {
+                1
+            } else {
+                self.options.max_repeat.max(1)
+            };
+            if count <= max_repeat {
+                result.push(c);
+            }
+        }
+
+        result
+    }
+}
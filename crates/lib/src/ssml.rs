@@ -0,0 +1,594 @@
+use crate::{
+    wav::Wav, AudioQuery, StyleId, SynthesisOptions, Synthesizer, VoicevoxError,
+    DEFAULT_SAMPLING_RATE,
+};
+use std::{collections::HashMap, time::Duration};
+use thiserror::Error;
+
+/// SSMLの解析・合成のエラー。
+#[derive(Error, Debug)]
+pub enum SsmlError {
+    /// XMLとしての解析に失敗した
+    #[error("XMLとしての解析に失敗した")]
+    Xml(#[from] roxmltree::Error),
+
+    /// ルート要素が`<speak>`ではない
+    #[error("ルート要素が`<speak>`ではない：`<{0}>`")]
+    NotSpeak(String),
+
+    /// 対応していない要素
+    #[error("{position}: 対応していない要素：`<{element}>`")]
+    UnsupportedElement {
+        element: String,
+        position: roxmltree::TextPos,
+    },
+
+    /// 必須の属性が無い
+    #[error("{position}: `<{element}>`に必須の属性`{attribute}`が無い")]
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+        position: roxmltree::TextPos,
+    },
+
+    /// 属性の値が不正
+    #[error("{position}: `<{element}>`の属性`{attribute}`の値が不正：`{value}`")]
+    InvalidAttribute {
+        element: String,
+        attribute: &'static str,
+        value: String,
+        position: roxmltree::TextPos,
+    },
+
+    /// 声に対応するスタイルIDが無い
+    #[error("{position}: 声`{name}`に対応するスタイルIDが無い")]
+    UnknownVoice {
+        name: String,
+        position: roxmltree::TextPos,
+    },
+
+    /// 音声合成に失敗した
+    #[error("音声合成に失敗した")]
+    Voicevox(#[from] VoicevoxError),
+
+    /// 合成した音声をWAVとして解析できない
+    #[error("合成した音声をWAVとして解析できない")]
+    InvalidWav,
+
+    /// 合成した音声のチャンネル数やサンプリングレートが揃っておらず、繋げられない
+    #[error("合成した音声のチャンネル数やサンプリングレートが揃っておらず、繋げられない")]
+    MismatchedWavFormat,
+}
+
+/// SSMLの解析・合成のオプション。
+#[derive(Debug, Clone, Default)]
+pub struct SsmlOptions {
+    /// `<voice name>`の名前とスタイルIDの対応。ここに無い名前は数値としてスタイルIDに解釈する。
+    pub voices: HashMap<String, StyleId>,
+    /// 疑問文の調整を有効にする。
+    pub enable_interrogative_upspeak: bool,
+}
+
+/// `<prosody>`による韻律の調整。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prosody {
+    /// 話速の倍率。
+    pub rate: f32,
+    /// 音高の変化量。モーラの音高と同じ対数の単位。
+    pub pitch: f32,
+    /// 音量の倍率。
+    pub volume: f32,
+}
+
+impl Default for Prosody {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            pitch: 0.0,
+            volume: 1.0,
+        }
+    }
+}
+
+impl Prosody {
+    /// AudioQueryに韻律の調整を適用する。
+    ///
+    /// 話速と音高はモーラごとの音素長・音高に、音量は[`AudioQuery::volume_scale`]に反映する。
+    pub fn apply(&self, audio_query: &mut AudioQuery) {
+        for accent_phrase in &mut audio_query.accent_phrases {
+            for mora in accent_phrase
+                .moras
+                .iter_mut()
+                .chain(accent_phrase.pause_mora.as_mut())
+            {
+                if let Some(consonant_length) = &mut mora.consonant_length {
+                    *consonant_length /= self.rate;
+                }
+                mora.vowel_length /= self.rate;
+                // 無声のモーラは音高0のままにする。
+                if mora.pitch > 0.0 {
+                    mora.pitch += self.pitch;
+                }
+            }
+        }
+        audio_query.volume_scale *= self.volume;
+    }
+
+    fn nest(&self, inner: &Prosody) -> Prosody {
+        Prosody {
+            rate: self.rate * inner.rate,
+            pitch: self.pitch + inner.pitch,
+            volume: self.volume * inner.volume,
+        }
+    }
+}
+
+/// SSMLを解析した結果の要素。
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsmlSegment {
+    /// 読み上げるテキスト。
+    Speech {
+        /// テキスト。
+        text: String,
+        /// `text`がAquesTalk風記法かどうか。
+        is_kana: bool,
+        /// 音声のスタイルID。
+        style_id: StyleId,
+        /// 韻律の調整。
+        prosody: Prosody,
+    },
+    /// 無音。
+    Break(Duration),
+    /// `<mark>`の位置。
+    Mark(String),
+}
+
+/// SSMLから生成したAudioQueryの列の要素。
+#[derive(Debug, Clone)]
+pub enum SsmlQuery {
+    /// 読み上げ。
+    Speech {
+        /// 韻律の調整を適用済みのAudioQuery。
+        audio_query: AudioQuery,
+        /// 音声のスタイルID。
+        style_id: StyleId,
+    },
+    /// 無音。
+    Break(Duration),
+    /// `<mark>`の位置。
+    Mark(String),
+}
+
+/// `<mark>`の位置の時刻。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsmlMark {
+    /// `<mark name>`の名前。
+    pub name: String,
+    /// 音声の先頭からの時刻。
+    pub time: Duration,
+}
+
+/// SSMLから合成した音声。
+#[derive(Debug, Clone)]
+pub struct SsmlAudio {
+    /// WAV形式の音声データ。
+    pub wav: Vec<u8>,
+    /// `<mark>`の時刻。
+    pub marks: Vec<SsmlMark>,
+}
+
+/// SSMLを解析する。
+///
+/// 対応している要素は`<speak>`、`<break>`、`<prosody>`、`<sub>`、`<say-as>`、`<voice>`、
+/// `<mark>`、`<p>`、`<s>`。`<say-as interpret-as="kana">`の中はAquesTalk風記法として扱う。
+///
+/// # Arguments
+/// * `ssml` - SSML。
+/// * `style_id` - `<voice>`の外側で使う音声のスタイルID。
+/// * `options` - オプション。
+pub fn parse_ssml(
+    ssml: &str,
+    style_id: StyleId,
+    options: &SsmlOptions,
+) -> std::result::Result<Vec<SsmlSegment>, SsmlError> {
+    let document = roxmltree::Document::parse(ssml)?;
+    let root = document.root_element();
+    if root.tag_name().name() != "speak" {
+        return Err(SsmlError::NotSpeak(root.tag_name().name().to_string()));
+    }
+
+    let mut parser = SsmlParser {
+        document: &document,
+        options,
+        segments: vec![],
+    };
+    parser.walk(
+        root,
+        &ParseState {
+            style_id,
+            prosody: Prosody::default(),
+            is_kana: false,
+        },
+    )?;
+
+    Ok(parser.segments)
+}
+
+impl Synthesizer {
+    /// SSMLからAudioQueryの列を生成する。
+    ///
+    /// # Arguments
+    /// * `ssml` - SSML。
+    /// * `style_id` - `<voice>`の外側で使う音声のスタイルID。
+    /// * `options` - オプション。
+    pub fn create_ssml_queries(
+        &self,
+        ssml: &str,
+        style_id: StyleId,
+        options: &SsmlOptions,
+    ) -> std::result::Result<Vec<SsmlQuery>, SsmlError> {
+        parse_ssml(ssml, style_id, options)?
+            .into_iter()
+            .map(|segment| {
+                Ok(match segment {
+                    SsmlSegment::Speech {
+                        text,
+                        is_kana,
+                        style_id,
+                        prosody,
+                    } => {
+                        let mut audio_query = if is_kana {
+                            self.create_audio_query_from_kana(&text, style_id)?
+                        } else {
+                            self.create_audio_query(&text, style_id)?
+                        };
+                        prosody.apply(&mut audio_query);
+                        SsmlQuery::Speech {
+                            audio_query,
+                            style_id,
+                        }
+                    }
+                    SsmlSegment::Break(duration) => SsmlQuery::Break(duration),
+                    SsmlSegment::Mark(name) => SsmlQuery::Mark(name),
+                })
+            })
+            .collect()
+    }
+
+    /// SSMLから音声を合成する。
+    ///
+    /// # Arguments
+    /// * `ssml` - SSML。
+    /// * `style_id` - `<voice>`の外側で使う音声のスタイルID。
+    /// * `options` - オプション。
+    ///
+    /// # Returns
+    /// 全体を繋げた音声と、`<mark>`の時刻。
+    pub fn synthesis_ssml(
+        &self,
+        ssml: &str,
+        style_id: StyleId,
+        options: &SsmlOptions,
+    ) -> std::result::Result<SsmlAudio, SsmlError> {
+        let synthesis_options = SynthesisOptions {
+            enable_interrogative_upspeak: options.enable_interrogative_upspeak,
+        };
+        let queries = self.create_ssml_queries(ssml, style_id, options)?;
+        let wavs = queries
+            .iter()
+            .map(|query| match query {
+                SsmlQuery::Speech {
+                    audio_query,
+                    style_id,
+                } => {
+                    let wav = self.synthesis(audio_query, *style_id, synthesis_options)?;
+                    Wav::parse(&wav).map(Some).ok_or(SsmlError::InvalidWav)
+                }
+                _ => Ok(None),
+            })
+            .collect::<std::result::Result<Vec<_>, SsmlError>>()?;
+
+        let mut combined = wavs
+            .iter()
+            .flatten()
+            .next()
+            .map(Wav::empty_like)
+            .unwrap_or(Wav {
+                channels: 1,
+                sample_rate: DEFAULT_SAMPLING_RATE,
+                bits_per_sample: 16,
+                data: vec![],
+            });
+        let mut marks = vec![];
+        for (query, wav) in queries.into_iter().zip(wavs) {
+            match query {
+                SsmlQuery::Speech { .. } => {
+                    let wav = wav.unwrap();
+                    if !combined.append(&wav) {
+                        return Err(SsmlError::MismatchedWavFormat);
+                    }
+                }
+                SsmlQuery::Break(duration) => combined.push_silence(duration),
+                SsmlQuery::Mark(name) => marks.push(SsmlMark {
+                    name,
+                    time: combined.duration(),
+                }),
+            }
+        }
+
+        Ok(SsmlAudio {
+            wav: combined.to_bytes(),
+            marks,
+        })
+    }
+}
+
+struct ParseState {
+    style_id: StyleId,
+    prosody: Prosody,
+    is_kana: bool,
+}
+
+struct SsmlParser<'a, 'input> {
+    document: &'a roxmltree::Document<'input>,
+    options: &'a SsmlOptions,
+    segments: Vec<SsmlSegment>,
+}
+
+impl SsmlParser<'_, '_> {
+    fn walk(
+        &mut self,
+        node: roxmltree::Node,
+        state: &ParseState,
+    ) -> std::result::Result<(), SsmlError> {
+        for child in node.children() {
+            if child.is_text() {
+                self.push_text(child.text().unwrap_or_default(), state);
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+
+            match child.tag_name().name() {
+                "p" | "s" => self.walk(child, state)?,
+                "break" => {
+                    let duration = self.parse_break(child)?;
+                    self.segments.push(SsmlSegment::Break(duration));
+                }
+                "mark" => {
+                    let name = self.required_attribute(child, "name")?;
+                    self.segments.push(SsmlSegment::Mark(name.to_string()));
+                }
+                "sub" => {
+                    let alias = self.required_attribute(child, "alias")?;
+                    self.push_text(alias, state);
+                }
+                "say-as" => {
+                    let interpret_as = self.required_attribute(child, "interpret-as")?;
+                    match interpret_as {
+                        "kana" => self.walk(
+                            child,
+                            &ParseState {
+                                is_kana: true,
+                                ..*state
+                            },
+                        )?,
+                        "characters" | "spell-out" => {
+                            let text: String = child
+                                .descendants()
+                                .filter(|node| node.is_text())
+                                .filter_map(|node| node.text())
+                                .collect();
+                            let text = text
+                                .chars()
+                                .filter(|c| !c.is_whitespace())
+                                .map(String::from)
+                                .collect::<Vec<_>>()
+                                .join("、");
+                            self.push_text(&text, state);
+                        }
+                        _ => self.walk(child, state)?,
+                    }
+                }
+                "prosody" => {
+                    let prosody = Prosody {
+                        rate: self.parse_attribute(child, "rate", parse_rate)?,
+                        pitch: self.parse_attribute(child, "pitch", parse_pitch)?,
+                        volume: self.parse_attribute(child, "volume", parse_volume)?,
+                    };
+                    self.walk(
+                        child,
+                        &ParseState {
+                            prosody: state.prosody.nest(&prosody),
+                            ..*state
+                        },
+                    )?;
+                }
+                "voice" => {
+                    let name = self.required_attribute(child, "name")?;
+                    let style_id = self
+                        .options
+                        .voices
+                        .get(name)
+                        .copied()
+                        .or_else(|| name.parse().ok())
+                        .ok_or_else(|| SsmlError::UnknownVoice {
+                            name: name.to_string(),
+                            position: self.position(child),
+                        })?;
+                    self.walk(child, &ParseState { style_id, ..*state })?;
+                }
+                element => {
+                    return Err(SsmlError::UnsupportedElement {
+                        element: element.to_string(),
+                        position: self.position(child),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_text(&mut self, text: &str, state: &ParseState) {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return;
+        }
+
+        // 同じ設定のテキストが続く場合は1つにまとめる。AquesTalk風記法は連結できないので除く。
+        if let Some(SsmlSegment::Speech {
+            text: last_text,
+            is_kana: false,
+            style_id,
+            prosody,
+        }) = self.segments.last_mut()
+        {
+            if !state.is_kana && *style_id == state.style_id && *prosody == state.prosody {
+                last_text.push_str(&text);
+                return;
+            }
+        }
+
+        self.segments.push(SsmlSegment::Speech {
+            text,
+            is_kana: state.is_kana,
+            style_id: state.style_id,
+            prosody: state.prosody,
+        });
+    }
+
+    fn parse_break(&self, node: roxmltree::Node) -> std::result::Result<Duration, SsmlError> {
+        if let Some(time) = node.attribute("time") {
+            return parse_time(time).ok_or_else(|| self.invalid_attribute(node, "time", time));
+        }
+
+        let seconds = match node.attribute("strength").unwrap_or("medium") {
+            "none" => 0.0,
+            "x-weak" => 0.1,
+            "weak" => 0.25,
+            "medium" => 0.5,
+            "strong" => 0.75,
+            "x-strong" => 1.0,
+            strength => return Err(self.invalid_attribute(node, "strength", strength)),
+        };
+        Ok(Duration::from_secs_f32(seconds))
+    }
+
+    fn parse_attribute(
+        &self,
+        node: roxmltree::Node,
+        attribute: &'static str,
+        parse: fn(&str) -> Option<f32>,
+    ) -> std::result::Result<f32, SsmlError> {
+        let default = match attribute {
+            "pitch" => 0.0,
+            _ => 1.0,
+        };
+        node.attribute(attribute).map_or(Ok(default), |value| {
+            parse(value).ok_or_else(|| self.invalid_attribute(node, attribute, value))
+        })
+    }
+
+    fn required_attribute<'a>(
+        &self,
+        node: roxmltree::Node<'a, '_>,
+        attribute: &'static str,
+    ) -> std::result::Result<&'a str, SsmlError> {
+        node.attribute(attribute)
+            .ok_or_else(|| SsmlError::MissingAttribute {
+                element: node.tag_name().name().to_string(),
+                attribute,
+                position: self.position(node),
+            })
+    }
+
+    fn invalid_attribute(
+        &self,
+        node: roxmltree::Node,
+        attribute: &'static str,
+        value: &str,
+    ) -> SsmlError {
+        SsmlError::InvalidAttribute {
+            element: node.tag_name().name().to_string(),
+            attribute,
+            value: value.to_string(),
+            position: self.position(node),
+        }
+    }
+
+    fn position(&self, node: roxmltree::Node) -> roxmltree::TextPos {
+        self.document.text_pos_at(node.range().start)
+    }
+}
+
+fn parse_time(value: &str) -> Option<Duration> {
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 0.001)
+    } else {
+        (value.strip_suffix('s')?, 1.0)
+    };
+    let seconds = number.trim().parse::<f64>().ok()? * scale;
+
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// `+20%`・`120%`・`1.2`のような倍率を解釈する。
+fn parse_ratio(value: &str) -> Option<f32> {
+    match value.strip_suffix('%') {
+        Some(percent) if percent.starts_with(['+', '-']) => {
+            Some(1.0 + percent.parse::<f32>().ok()? / 100.0)
+        }
+        Some(percent) => Some(percent.parse::<f32>().ok()? / 100.0),
+        None => value.parse().ok(),
+    }
+    .filter(|ratio| ratio.is_finite() && *ratio >= 0.0)
+}
+
+fn parse_rate(value: &str) -> Option<f32> {
+    match value {
+        "x-slow" => Some(0.5),
+        "slow" => Some(0.75),
+        "medium" | "default" => Some(1.0),
+        "fast" => Some(1.25),
+        "x-fast" => Some(1.5),
+        _ => parse_ratio(value).filter(|rate| *rate > 0.0),
+    }
+}
+
+fn parse_pitch(value: &str) -> Option<f32> {
+    let pitch = match value {
+        "x-low" => -0.1,
+        "low" => -0.05,
+        "medium" | "default" => 0.0,
+        "high" => 0.05,
+        "x-high" => 0.1,
+        _ => {
+            if let Some(semitones) = value.strip_suffix("st") {
+                semitones.parse::<f32>().ok()? * std::f32::consts::LN_2 / 12.0
+            } else if value.ends_with('%') {
+                parse_ratio(value).filter(|ratio| *ratio > 0.0)?.ln()
+            } else {
+                value.parse().ok()?
+            }
+        }
+    };
+
+    Some(pitch).filter(|pitch: &f32| pitch.is_finite())
+}
+
+fn parse_volume(value: &str) -> Option<f32> {
+    match value {
+        "silent" => Some(0.0),
+        "x-soft" => Some(0.25),
+        "soft" => Some(0.5),
+        "medium" | "default" => Some(1.0),
+        "loud" => Some(1.5),
+        "x-loud" => Some(2.0),
+        _ => match value.strip_suffix("dB") {
+            Some(decibels) => Some(10f32.powf(decibels.parse::<f32>().ok()? / 20.0)),
+            None => parse_ratio(value),
+        },
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{i32_to_result, AccentPhrase, AudioQuery, Result, SpeakerMeta, StyleId, VoiceModel};
+use crate::{
+    i32_to_result, AccentPhrase, AudioQuery, Preset, Result, SpeakerMeta, StyleId, VoiceModel,
+};
 use std::{
     ffi::{CStr, CString},
     mem::MaybeUninit,
@@ -345,6 +347,34 @@ impl Synthesizer {
         Ok(result)
     }
 
+    /// 日本語テキストから、プリセットを適用して音声を合成する。
+    ///
+    /// プリセットにスタイルIDが紐付けられている場合は、そのスタイルで合成する。
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - 音声合成するテキスト。
+    /// * `style_id` - プリセットにスタイルIDが無い場合に使う、音声のスタイルID。
+    /// * `preset` - 適用するプリセット。
+    /// * `options` - 音声合成のオプション。
+    ///
+    /// # Returns
+    ///
+    /// WAV形式の音声データ。
+    pub fn tts_with_preset(
+        &self,
+        text: &str,
+        style_id: StyleId,
+        preset: &Preset,
+        options: TtsOptions,
+    ) -> Result<Vec<u8>> {
+        let style_id = preset.style_id.unwrap_or(style_id);
+        let mut audio_query = self.create_audio_query(text, style_id)?;
+        preset.apply(&mut audio_query);
+
+        self.synthesis(&audio_query, style_id, options.into())
+    }
+
     /// AquesTalk風記法のカナから音声を合成する。
     ///
     /// # Arguments
@@ -440,6 +470,14 @@ impl From<sys::VoicevoxTtsOptions> for TtsOptions {
     }
 }
 
+impl From<TtsOptions> for SynthesisOptions {
+    fn from(options: TtsOptions) -> SynthesisOptions {
+        SynthesisOptions {
+            enable_interrogative_upspeak: options.enable_interrogative_upspeak,
+        }
+    }
+}
+
 impl From<SynthesisOptions> for sys::VoicevoxSynthesisOptions {
     fn from(options: SynthesisOptions) -> sys::VoicevoxSynthesisOptions {
         sys::VoicevoxSynthesisOptions {
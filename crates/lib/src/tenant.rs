@@ -0,0 +1,217 @@
+use crate::{
+    AudioQuery, InitializeOptions, OpenJtalkRc, Result, StyleId, Synthesizer, TtsOptions, UserDict,
+    VoiceModel,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// [`TenantManager`]のオプション。
+#[derive(Debug, Clone, Copy)]
+pub struct TenantOptions {
+    /// 同時に持つコンテキストの最大の数。超えた場合は最も長く使われていないテナントを追い出す。
+    ///
+    /// コンテキストはそれぞれ音声モデルを読み込んだ`Synthesizer`を持つので、メモリの使用量は
+    /// この数に比例する。増やすと、テナントを切り替えるときの辞書の入れ替えが減る。
+    pub max_contexts: usize,
+    /// 追い出したテナントのコンテキストを、次のテナントのために残しておく最大の数。
+    pub max_idle_contexts: usize,
+    /// この時間だけ使われなかったテナントを、[`TenantManager::evict_idle`]で追い出す。
+    pub idle_timeout: Duration,
+    /// `Synthesizer`のオプション。
+    pub initialize_options: InitializeOptions,
+}
+
+impl Default for TenantOptions {
+    fn default() -> Self {
+        Self {
+            max_contexts: 1,
+            max_idle_contexts: 1,
+            idle_timeout: Duration::from_secs(30 * 60),
+            initialize_options: Default::default(),
+        }
+    }
+}
+
+/// テナント専用の`OpenJtalkRc`と`Synthesizer`。
+struct TenantContext {
+    open_jtalk: OpenJtalkRc,
+    synthesizer: Synthesizer,
+    last_used: Instant,
+}
+
+/// Discordのサーバーなどのテナントごとに、別々のユーザー辞書を使って音声合成する。
+///
+/// `OpenJtalkRc::use_user_dict`は`OpenJtalkRc`を共有するすべての`Synthesizer`に影響するので、
+/// テナントごとに`OpenJtalkRc`と`Synthesizer`のコンテキストを割り当てる。コンテキストはそれぞれ
+/// すべての音声モデルを読み込むので、音声モデルの複製をコンテキストの数だけ持つことになる。既定では
+/// コンテキストを1つだけ持ち、テナントが切り替わるたびに辞書を入れ替えて使いまわす。
+pub struct TenantManager<K> {
+    dict_dir: PathBuf,
+    voice_models: Vec<VoiceModel>,
+    options: TenantOptions,
+    user_dicts: HashMap<K, UserDict>,
+    active: HashMap<K, TenantContext>,
+    idle: Vec<TenantContext>,
+}
+
+impl<K: Eq + Hash + Clone> TenantManager<K> {
+    /// マネージャーを作成する。コンテキストは最初に使うときに作成する。
+    ///
+    /// # Arguments
+    /// * `dict_dir` - Open JTalkの辞書ディレクトリ。
+    /// * `voice_models` - すべてのコンテキストで読み込む音声モデル。
+    /// * `options` - オプション。
+    pub fn new<S: AsRef<Path>>(
+        dict_dir: S,
+        voice_models: Vec<VoiceModel>,
+        options: TenantOptions,
+    ) -> Self {
+        Self {
+            dict_dir: dict_dir.as_ref().to_path_buf(),
+            voice_models,
+            options,
+            user_dicts: HashMap::new(),
+            active: HashMap::new(),
+            idle: vec![],
+        }
+    }
+
+    /// テナントのユーザー辞書を設定する。コンテキストがある場合はすぐに適用する。
+    pub fn set_user_dict(&mut self, tenant: K, user_dict: UserDict) -> Result<()> {
+        if let Some(context) = self.active.get(&tenant) {
+            context.open_jtalk.use_user_dict(&user_dict)?;
+        }
+        self.user_dicts.insert(tenant, user_dict);
+
+        Ok(())
+    }
+
+    /// テナントのユーザー辞書。
+    pub fn user_dict(&self, tenant: &K) -> Option<&UserDict> {
+        self.user_dicts.get(tenant)
+    }
+
+    /// テナントを削除する。ユーザー辞書も削除する。
+    pub fn remove_tenant(&mut self, tenant: &K) -> Option<UserDict> {
+        if let Some(context) = self.active.remove(tenant) {
+            self.release(context);
+        }
+        self.user_dicts.remove(tenant)
+    }
+
+    /// コンテキストを持っているテナント。
+    pub fn active_tenants(&self) -> impl Iterator<Item = &K> {
+        self.active.keys()
+    }
+
+    /// テナントのユーザー辞書を適用した`Synthesizer`を返す。
+    ///
+    /// コンテキストが無い場合は、使われていないコンテキストを使いまわすか新しく作成する。
+    /// コンテキストの数が上限に達している場合は、最も長く使われていないテナントを追い出す。
+    pub fn synthesizer(&mut self, tenant: &K) -> Result<&Synthesizer> {
+        if !self.active.contains_key(tenant) {
+            let context = self.acquire(tenant)?;
+            self.active.insert(tenant.clone(), context);
+        }
+        let context = self.active.get_mut(tenant).unwrap();
+        context.last_used = Instant::now();
+
+        Ok(&context.synthesizer)
+    }
+
+    /// テナントのユーザー辞書を使ってAudioQueryを生成する。
+    pub fn create_audio_query(
+        &mut self,
+        tenant: &K,
+        text: &str,
+        style_id: StyleId,
+    ) -> Result<AudioQuery> {
+        self.synthesizer(tenant)?.create_audio_query(text, style_id)
+    }
+
+    /// テナントのユーザー辞書を使って音声を合成する。
+    ///
+    /// # Returns
+    /// WAV形式の音声データ。
+    pub fn tts(
+        &mut self,
+        tenant: &K,
+        text: &str,
+        style_id: StyleId,
+        options: TtsOptions,
+    ) -> Result<Vec<u8>> {
+        self.synthesizer(tenant)?.tts(text, style_id, options)
+    }
+
+    /// [`TenantOptions::idle_timeout`]より長く使われていないテナントのコンテキストを解放する。
+    ///
+    /// # Returns
+    ///
+    /// 追い出したテナント。
+    pub fn evict_idle(&mut self) -> Vec<K> {
+        let now = Instant::now();
+        let evicted: Vec<K> = self
+            .active
+            .iter()
+            .filter(|(_, context)| {
+                now.duration_since(context.last_used) >= self.options.idle_timeout
+            })
+            .map(|(tenant, _)| tenant.clone())
+            .collect();
+        for tenant in &evicted {
+            let context = self.active.remove(tenant).unwrap();
+            self.release(context);
+        }
+
+        evicted
+    }
+
+    fn acquire(&mut self, tenant: &K) -> Result<TenantContext> {
+        if self.active.len() >= self.options.max_contexts.max(1) {
+            let least_recent = self
+                .active
+                .iter()
+                .min_by_key(|(_, context)| context.last_used)
+                .map(|(tenant, _)| tenant.clone())
+                .unwrap();
+            let context = self.active.remove(&least_recent).unwrap();
+            self.release(context);
+        }
+
+        let context = match self.idle.pop() {
+            Some(context) => context,
+            None => self.create_context()?,
+        };
+        // 前のテナントの辞書が残らないように、辞書が無いテナントには空の辞書を適用する。
+        match self.user_dicts.get(tenant) {
+            Some(user_dict) => context.open_jtalk.use_user_dict(user_dict)?,
+            None => context.open_jtalk.use_user_dict(&UserDict::new()?)?,
+        }
+
+        Ok(context)
+    }
+
+    fn release(&mut self, context: TenantContext) {
+        if self.idle.len() < self.options.max_idle_contexts {
+            self.idle.push(context);
+        }
+    }
+
+    fn create_context(&self) -> Result<TenantContext> {
+        let open_jtalk = OpenJtalkRc::new(&self.dict_dir)?;
+        let synthesizer = Synthesizer::new(&open_jtalk, self.options.initialize_options)?;
+        for voice_model in &self.voice_models {
+            synthesizer.load_voice_model(voice_model)?;
+        }
+
+        Ok(TenantContext {
+            open_jtalk,
+            synthesizer,
+            last_used: Instant::now(),
+        })
+    }
+}
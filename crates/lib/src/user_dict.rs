@@ -1,8 +1,10 @@
-use crate::{i32_to_result, Result};
+use crate::{asset::StagedPath, i32_to_result, Result, UserDictFileError};
 use std::{
+    cell::RefCell,
     collections::{BTreeMap, HashMap},
     ffi::{CStr, CString},
     mem::MaybeUninit,
+    rc::Rc,
 };
 use voicevox_core_sys as sys;
 
@@ -12,7 +14,7 @@ use serde::{Deserialize, Serialize};
 pub use uuid::Uuid;
 
 /// ユーザー辞書の単語の種類。
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum UserDictWordType {
     /// 固有名詞。
     ProperNoun,
@@ -27,7 +29,7 @@ pub enum UserDictWordType {
 }
 
 /// ユーザー辞書の単語。
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct UserDictWord {
     /// 表記。
     pub surface: String,
@@ -55,6 +57,8 @@ impl UserDictWord {
 /// ユーザー辞書。
 pub struct UserDict {
     pub(crate) inner: *mut sys::VoicevoxUserDict,
+    /// 単語の一覧のキャッシュ。辞書を変更したときに破棄する。
+    words: RefCell<Option<Rc<IndexMap<Uuid, UserDictWord>>>>,
 }
 
 impl UserDict {
@@ -62,17 +66,45 @@ impl UserDict {
     pub fn new() -> Result<Self> {
         let inner = unsafe { sys::voicevox_user_dict_new() };
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            words: RefCell::new(None),
+        })
+    }
+
+    /// `(UUID, 単語)`の組からユーザー辞書を構築する。UUIDはそのまま引き継ぐ。
+    pub(crate) fn from_words<'a>(
+        words: impl IntoIterator<Item = (&'a Uuid, &'a UserDictWord)>,
+    ) -> std::result::Result<Self, UserDictFileError> {
+        let words: IndexMap<&Uuid, &UserDictWord> = words.into_iter().collect();
+
+        let user_dict = Self::new()?;
+        // UUIDを保ったまま単語を入れる方法は、ファイルからの読み込みしか無い。一時ファイルは`staged`を
+        // 破棄するときに削除される。
+        let json = serde_json::to_string(&words).unwrap();
+        let staged = StagedPath::file(&mut json.as_bytes(), "json")?;
+        if staged.path().to_str().is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "一時ファイルのパスがUTF-8ではない",
+            )
+            .into());
+        }
+        user_dict.load(staged.path())?;
+
+        Ok(user_dict)
     }
 
     /// ユーザー辞書をファイルから読み込む。
     pub fn load<S: AsRef<std::path::Path>>(&self, path: S) -> Result<()> {
         let path = CString::new(path.as_ref().to_str().unwrap()).unwrap();
+        self.invalidate();
         i32_to_result(unsafe { sys::voicevox_user_dict_load(self.inner, path.as_ptr()) })
     }
 
     /// 他のユーザー辞書を読み込む。
     pub fn import(&self, other: &UserDict) -> Result<()> {
+        self.invalidate();
         i32_to_result(unsafe { sys::voicevox_user_dict_import(self.inner, other.inner) })
     }
 
@@ -85,8 +117,13 @@ impl UserDict {
     /// ユーザー辞書に単語を追加する。
     pub fn add_word(&self, word: UserDictWord) -> Result<Uuid> {
         let mut word_uuid = [0u8; 16];
+        self.invalidate();
         i32_to_result(unsafe {
-            sys::voicevox_user_dict_add_word(self.inner, &word.into(), &mut word_uuid)
+            sys::voicevox_user_dict_add_word(
+                self.inner,
+                RawUserDictWord::new(&word).as_ptr(),
+                &mut word_uuid,
+            )
         })?;
 
         Ok(uuid::Uuid::from_slice(&word_uuid).unwrap())
@@ -94,6 +131,7 @@ impl UserDict {
 
     /// ユーザー辞書から単語を削除する。
     pub fn remove_word(&self, word_uuid: &Uuid) -> Result<()> {
+        self.invalidate();
         i32_to_result(unsafe {
             sys::voicevox_user_dict_remove_word(self.inner, word_uuid.as_bytes().as_ptr() as _)
         })
@@ -101,11 +139,12 @@ impl UserDict {
 
     /// ユーザー辞書の単語を更新する。
     pub fn update_word(&self, word_uuid: Uuid, word: UserDictWord) -> Result<()> {
+        self.invalidate();
         i32_to_result(unsafe {
             sys::voicevox_user_dict_update_word(
                 self.inner,
                 word_uuid.as_bytes().as_ptr() as _,
-                &word.into(),
+                RawUserDictWord::new(&word).as_ptr(),
             )
         })
     }
@@ -128,6 +167,114 @@ impl UserDict {
 
         Ok(json)
     }
+
+    fn invalidate(&self) {
+        self.words.borrow_mut().take();
+    }
+
+    /// 単語の一覧を返す。ネイティブの辞書から読み出すのは、辞書を変更した後の最初の呼び出しのみ。
+    fn words(&self) -> Result<Rc<IndexMap<Uuid, UserDictWord>>> {
+        if let Some(words) = &*self.words.borrow() {
+            return Ok(words.clone());
+        }
+
+        let words: Rc<IndexMap<Uuid, UserDictWord>> =
+            Rc::new(serde_json::from_str(&self.to_json()?).unwrap());
+        *self.words.borrow_mut() = Some(words.clone());
+
+        Ok(words)
+    }
+
+    /// 単語の数。
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.words()?.len())
+    }
+
+    /// 単語が無いかどうか。
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.words()?.is_empty())
+    }
+
+    /// UUIDで単語を引く。
+    pub fn get(&self, word_uuid: &Uuid) -> Result<Option<UserDictWord>> {
+        Ok(self.words()?.get(word_uuid).cloned())
+    }
+
+    /// 表記が一致する単語を探す。半角の英数字・記号は全角として比較する。
+    pub fn find_by_surface(&self, surface: &str) -> Result<Vec<(Uuid, UserDictWord)>> {
+        let surface = to_full_width(surface);
+        self.find(|word| to_full_width(&word.surface) == surface)
+    }
+
+    /// 読みが一致する単語を探す。ひらがなはカタカナとして比較する。
+    pub fn find_by_pronunciation(&self, pronunciation: &str) -> Result<Vec<(Uuid, UserDictWord)>> {
+        let pronunciation = hiragana_to_katakana(pronunciation);
+        self.find(|word| word.pronunciation == pronunciation)
+    }
+
+    fn find(&self, predicate: impl Fn(&UserDictWord) -> bool) -> Result<Vec<(Uuid, UserDictWord)>> {
+        Ok(self
+            .words()?
+            .iter()
+            .filter(|(_, word)| predicate(word))
+            .map(|(uuid, word)| (*uuid, word.clone()))
+            .collect())
+    }
+
+    /// 単語を`(UUID, 単語)`の組で順に返す。
+    ///
+    /// 呼び出した時点の単語の一覧を返し、その後の辞書の変更は反映されない。
+    pub fn iter(&self) -> Result<UserDictIter> {
+        Ok(UserDictIter {
+            words: self.words()?,
+            index: 0,
+        })
+    }
+}
+
+/// [`UserDict::iter`]が返すイテレータ。
+pub struct UserDictIter {
+    words: Rc<IndexMap<Uuid, UserDictWord>>,
+    index: usize,
+}
+
+impl Iterator for UserDictIter {
+    type Item = (Uuid, UserDictWord);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (uuid, word) = self.words.get_index(self.index)?;
+        self.index += 1;
+
+        Some((*uuid, word.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.words.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for UserDictIter {}
+
+/// 半角の英数字・記号と空白を全角にする。
+pub(crate) fn to_full_width(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '!'..='~' => char::from_u32(c as u32 + 0xfee0).unwrap(),
+            ' ' => '\u{3000}',
+            c => c,
+        })
+        .collect()
+}
+
+/// ひらがなをカタカナにする。
+pub(crate) fn hiragana_to_katakana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ぁ'..='ゖ' => char::from_u32(c as u32 + 0x60).unwrap(),
+            c => c,
+        })
+        .collect()
 }
 
 impl Clone for UserDict {
@@ -144,9 +291,45 @@ impl Serialize for UserDict {
         &self,
         serializer: S,
     ) -> std::result::Result<S::Ok, S::Error> {
-        let json = self.to_json().unwrap();
-        let map: HashMap<Uuid, UserDictWord> = serde_json::from_str(&json).unwrap();
-        map.serialize(serializer)
+        let words = self.words().map_err(serde::ser::Error::custom)?;
+        words.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for UserDict {
+    fn deserialize<D: serde::de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let words = IndexMap::<Uuid, UserDictWord>::deserialize(deserializer)?;
+        UserDict::from_words(&words).map_err(serde::de::Error::custom)
+    }
+}
+
+/// 単語を追加したユーザー辞書を構築する。UUIDは新しく割り当てる。
+///
+/// # Panics
+///
+/// 単語を追加できなかった場合。
+impl FromIterator<UserDictWord> for UserDict {
+    fn from_iter<I: IntoIterator<Item = UserDictWord>>(iter: I) -> Self {
+        let user_dict = UserDict::new().unwrap();
+        for word in iter {
+            user_dict.add_word(word).unwrap();
+        }
+
+        user_dict
+    }
+}
+
+/// `(UUID, 単語)`の組からユーザー辞書を構築する。UUIDはそのまま引き継ぐ。
+///
+/// # Panics
+///
+/// 単語を読み込めなかった場合。
+impl FromIterator<(Uuid, UserDictWord)> for UserDict {
+    fn from_iter<I: IntoIterator<Item = (Uuid, UserDictWord)>>(iter: I) -> Self {
+        let words: IndexMap<Uuid, UserDictWord> = iter.into_iter().collect();
+        UserDict::from_words(&words).unwrap()
     }
 }
 
@@ -154,14 +337,13 @@ macro_rules! into_map {
     ($t:ident, $n:ident) => {
         impl From<UserDict> for $t<Uuid, UserDictWord> {
             fn from(user_dict: UserDict) -> Self {
-                let json = user_dict.to_json().unwrap();
-                serde_json::from_str(&json).unwrap()
+                user_dict.$n().unwrap()
             }
         }
 
         impl UserDict {
             pub fn $n(&self) -> Result<$t<Uuid, UserDictWord>> {
-                Ok(self.clone().into())
+                Ok(self.iter()?.collect())
             }
         }
     };
@@ -202,19 +384,36 @@ impl From<sys::VoicevoxUserDictWord> for UserDictWord {
     }
 }
 
-impl From<UserDictWord> for sys::VoicevoxUserDictWord {
-    fn from(word: UserDictWord) -> Self {
-        let surface = CString::new(word.surface).unwrap();
-        let pronunciation = CString::new(word.pronunciation).unwrap();
+/// ネイティブの関数に渡す単語。文字列は、この値と同じ期間だけ有効。
+struct RawUserDictWord {
+    raw: sys::VoicevoxUserDictWord,
+    _surface: CString,
+    _pronunciation: CString,
+}
 
-        Self {
-            surface: surface.into_raw(),
-            pronunciation: pronunciation.into_raw(),
+impl RawUserDictWord {
+    fn new(word: &UserDictWord) -> Self {
+        let surface = CString::new(word.surface.as_str()).unwrap();
+        let pronunciation = CString::new(word.pronunciation.as_str()).unwrap();
+        // `CString`を移動してもヒープ上の文字列は移動しないので、ポインタは有効なまま。
+        let raw = sys::VoicevoxUserDictWord {
+            surface: surface.as_ptr(),
+            pronunciation: pronunciation.as_ptr(),
             accent_type: word.accent_type,
             word_type: word.word_type.into(),
             priority: word.priority,
+        };
+
+        Self {
+            raw,
+            _surface: surface,
+            _pronunciation: pronunciation,
         }
     }
+
+    fn as_ptr(&self) -> *const sys::VoicevoxUserDictWord {
+        &self.raw
+    }
 }
 
 impl From<sys::VoicevoxUserDictWordType> for UserDictWordType {
@@ -0,0 +1,398 @@
+use crate::{
+    user_dict::hiragana_to_katakana, UserDict, UserDictWord, UserDictWordType, VoicevoxError,
+    DEFAULT_USER_DICT_PRIORITY,
+};
+use std::path::Path;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// ユーザー辞書を読み書きするテキスト形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserDictFormat {
+    /// `surface,pronunciation,accent_type,word_type,priority`の列からなるCSV。
+    ///
+    /// 1行目が`surface`から始まる場合は見出しとして読み飛ばす。`accent_type`と`priority`は省略でき、
+    /// `word_type`には`PROPER_NOUN`などの名前か品詞名を書ける。
+    Csv,
+    /// Microsoft IMEのテキスト形式（`よみ<TAB>語句<TAB>品詞`）。
+    MsIme,
+    /// Google日本語入力のテキスト形式（`よみ<TAB>単語<TAB>品詞<TAB>コメント`）。
+    GoogleIme,
+}
+
+/// 辞書ファイルの読み書きのエラー。
+#[derive(Error, Debug)]
+pub enum UserDictFileError {
+    /// ファイルの読み書きに失敗した
+    #[error("ファイルの読み書きに失敗した")]
+    Io(#[from] std::io::Error),
+
+    /// 文字コードが不正
+    #[error("文字コードが不正")]
+    Encoding,
+
+    /// ユーザー辞書の操作に失敗した
+    #[error("ユーザー辞書の操作に失敗した")]
+    Voicevox(#[from] VoicevoxError),
+}
+
+/// 辞書ファイルの行のエラーの種類。
+#[derive(Error, Debug)]
+pub enum UserDictLineErrorKind {
+    /// 列が足りない
+    #[error("列が足りない")]
+    MissingColumn,
+
+    /// 数値が不正
+    #[error("数値が不正：{0}")]
+    InvalidNumber(String),
+
+    /// 品詞が不正
+    #[error("品詞が不正：{0}")]
+    UnknownPartOfSpeech(String),
+
+    /// CSVとして不正
+    #[error("CSVとして不正")]
+    Csv(#[from] csv::Error),
+
+    /// 単語を追加できなかった
+    #[error("単語を追加できなかった")]
+    Voicevox(#[from] VoicevoxError),
+}
+
+/// 辞書ファイルの行のエラー。
+#[derive(Error, Debug)]
+#[error("{line}行目：{kind}")]
+pub struct UserDictLineError {
+    /// 1から始まる行番号。
+    pub line: usize,
+    /// エラーの種類。
+    pub kind: UserDictLineErrorKind,
+}
+
+/// 辞書ファイルの読み込みの結果。
+#[derive(Debug, Default)]
+pub struct UserDictImportReport {
+    /// 追加した単語のUUID。
+    pub added: Vec<Uuid>,
+    /// 追加できなかった行。
+    pub errors: Vec<UserDictLineError>,
+}
+
+impl UserDictWordType {
+    /// IMEの品詞名から単語の種類を決める。
+    ///
+    /// 人名・地名などは固有名詞、動詞の活用の種類はすべて動詞、接尾語はすべて接尾辞とみなす。
+    pub fn from_part_of_speech(part_of_speech: &str) -> Option<Self> {
+        let part_of_speech = part_of_speech.trim();
+        let word_type = match part_of_speech {
+            "固有名詞"
+            | "人名"
+            | "姓"
+            | "名"
+            | "地名"
+            | "組織"
+            | "地名その他"
+            | "組織名"
+            | "固有名詞その他" => Self::ProperNoun,
+            "名詞"
+            | "普通名詞"
+            | "さ変名詞"
+            | "サ変名詞"
+            | "名詞サ変"
+            | "名詞形動"
+            | "形容動詞"
+            | "ナ形容詞"
+            | "短縮よみ"
+            | "顔文字"
+            | "記号"
+            | "数"
+            | "アルファベット" => Self::CommonNoun,
+            "形容詞" => Self::Adjective,
+            _ if part_of_speech.starts_with("動詞") => Self::Verb,
+            _ if part_of_speech.starts_with("接尾") => Self::Suffix,
+            _ => return None,
+        };
+
+        Some(word_type)
+    }
+
+    /// IMEの品詞名。動詞は活用の種類がわからないため`動詞一段`とする。
+    pub fn to_part_of_speech(self) -> &'static str {
+        match self {
+            Self::ProperNoun => "固有名詞",
+            Self::CommonNoun => "名詞",
+            Self::Verb => "動詞一段",
+            Self::Adjective => "形容詞",
+            Self::Suffix => "接尾一般",
+        }
+    }
+
+    fn to_name(self) -> &'static str {
+        match self {
+            Self::ProperNoun => "PROPER_NOUN",
+            Self::CommonNoun => "COMMON_NOUN",
+            Self::Verb => "VERB",
+            Self::Adjective => "ADJECTIVE",
+            Self::Suffix => "SUFFIX",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let normalized = name.trim().replace('_', "").to_ascii_lowercase();
+        let word_type = match normalized.as_str() {
+            "propernoun" => Self::ProperNoun,
+            "commonnoun" => Self::CommonNoun,
+            "verb" => Self::Verb,
+            "adjective" => Self::Adjective,
+            "suffix" => Self::Suffix,
+            _ => return Self::from_part_of_speech(name),
+        };
+
+        Some(word_type)
+    }
+}
+
+const MS_IME_HEADER: &str = "!Microsoft IME Dictionary Tool";
+
+impl UserDict {
+    /// テキスト形式の辞書の単語を追加する。
+    ///
+    /// 不正な行は読み飛ばし、[`UserDictImportReport::errors`]に行番号とともに記録する。
+    pub fn import_text(&self, text: &str, format: UserDictFormat) -> UserDictImportReport {
+        let rows = match format {
+            UserDictFormat::Csv => parse_csv(text),
+            UserDictFormat::MsIme | UserDictFormat::GoogleIme => parse_ime(text),
+        };
+
+        let mut report = UserDictImportReport::default();
+        for (line, row) in rows {
+            match row.and_then(|word| Ok(self.add_word(word)?)) {
+                Ok(uuid) => report.added.push(uuid),
+                Err(kind) => report.errors.push(UserDictLineError { line, kind }),
+            }
+        }
+
+        report
+    }
+
+    /// 辞書をテキスト形式に変換する。
+    ///
+    /// IMEの形式では読みをひらがなにする。アクセント型と優先度は失われる。
+    pub fn export_text(&self, format: UserDictFormat) -> crate::Result<String> {
+        let mut text = String::new();
+        match format {
+            UserDictFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(vec![]);
+                writer
+                    .write_record([
+                        "surface",
+                        "pronunciation",
+                        "accent_type",
+                        "word_type",
+                        "priority",
+                    ])
+                    .unwrap();
+                for (_, word) in self.iter()? {
+                    writer
+                        .write_record([
+                            word.surface.as_str(),
+                            word.pronunciation.as_str(),
+                            &word.accent_type.to_string(),
+                            word.word_type.to_name(),
+                            &word.priority.to_string(),
+                        ])
+                        .unwrap();
+                }
+                text = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+            }
+            UserDictFormat::MsIme | UserDictFormat::GoogleIme => {
+                if format == UserDictFormat::MsIme {
+                    text.push_str(MS_IME_HEADER);
+                    text.push_str("\r\n!Format:WORDLIST\r\n\r\n");
+                }
+                let newline = if format == UserDictFormat::MsIme {
+                    "\r\n"
+                } else {
+                    "\n"
+                };
+                for (_, word) in self.iter()? {
+                    text.push_str(&format!(
+                        "{}\t{}\t{}{}",
+                        katakana_to_hiragana(&word.pronunciation),
+                        word.surface,
+                        word.word_type.to_part_of_speech(),
+                        newline
+                    ));
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// 辞書ファイルの単語を追加する。
+    ///
+    /// UTF-8とBOM付きのUTF-16を読める。
+    pub fn import_file<S: AsRef<Path>>(
+        &self,
+        path: S,
+        format: UserDictFormat,
+    ) -> std::result::Result<UserDictImportReport, UserDictFileError> {
+        let bytes = std::fs::read(path)?;
+        let text = decode_text(&bytes).ok_or(UserDictFileError::Encoding)?;
+
+        Ok(self.import_text(&text, format))
+    }
+
+    /// 辞書をファイルに保存する。
+    ///
+    /// Microsoft IMEの形式はBOM付きのUTF-16LE、それ以外はUTF-8で保存する。
+    pub fn export_file<S: AsRef<Path>>(
+        &self,
+        path: S,
+        format: UserDictFormat,
+    ) -> std::result::Result<(), UserDictFileError> {
+        let text = self.export_text(format)?;
+        let bytes = match format {
+            UserDictFormat::MsIme => [0xff, 0xfe]
+                .into_iter()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect(),
+            _ => text.into_bytes(),
+        };
+        std::fs::write(path, bytes)?;
+
+        Ok(())
+    }
+}
+
+type Row = std::result::Result<UserDictWord, UserDictLineErrorKind>;
+
+fn parse_csv(text: &str) -> Vec<(usize, Row)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .comment(Some(b'#'))
+        .from_reader(text.as_bytes());
+
+    let mut rows = vec![];
+    for (index, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                let line = error
+                    .position()
+                    .map_or(index + 1, |position| position.line() as usize);
+                rows.push((line, Err(error.into())));
+                continue;
+            }
+        };
+        let line = record
+            .position()
+            .map_or(index + 1, |position| position.line() as usize);
+        if index == 0 && record.get(0).map(str::trim) == Some("surface") {
+            continue;
+        }
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        rows.push((line, parse_csv_record(&record)));
+    }
+
+    rows
+}
+
+fn parse_csv_record(record: &csv::StringRecord) -> Row {
+    let field = |index: usize| {
+        record
+            .get(index)
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+    };
+    let number = |index: usize, default: u32| match field(index) {
+        Some(field) => field
+            .parse::<u32>()
+            .map_err(|_| UserDictLineErrorKind::InvalidNumber(field.to_string())),
+        None => Ok(default),
+    };
+
+    let surface = field(0).ok_or(UserDictLineErrorKind::MissingColumn)?;
+    let pronunciation = field(1).ok_or(UserDictLineErrorKind::MissingColumn)?;
+    let accent_type = number(2, 0)? as usize;
+    let word_type = match field(3) {
+        Some(name) => UserDictWordType::from_name(name)
+            .ok_or_else(|| UserDictLineErrorKind::UnknownPartOfSpeech(name.to_string()))?,
+        None => UserDictWordType::ProperNoun,
+    };
+    let priority = number(4, DEFAULT_USER_DICT_PRIORITY)?;
+
+    Ok(UserDictWord {
+        surface: surface.to_string(),
+        pronunciation: hiragana_to_katakana(pronunciation),
+        accent_type,
+        word_type,
+        priority,
+    })
+}
+
+fn parse_ime(text: &str) -> Vec<(usize, Row)> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.trim_end_matches('\r');
+            // `!`はMicrosoft IME、`#`はGoogle日本語入力の見出しやコメント。
+            if line.trim().is_empty() || line.starts_with('!') || line.starts_with('#') {
+                return None;
+            }
+            Some((index + 1, parse_ime_line(line)))
+        })
+        .collect()
+}
+
+fn parse_ime_line(line: &str) -> Row {
+    let mut columns = line.split('\t');
+    let (Some(reading), Some(surface), Some(part_of_speech)) =
+        (columns.next(), columns.next(), columns.next())
+    else {
+        return Err(UserDictLineErrorKind::MissingColumn);
+    };
+    if reading.is_empty() || surface.is_empty() {
+        return Err(UserDictLineErrorKind::MissingColumn);
+    }
+    let word_type = UserDictWordType::from_part_of_speech(part_of_speech)
+        .ok_or_else(|| UserDictLineErrorKind::UnknownPartOfSpeech(part_of_speech.to_string()))?;
+
+    Ok(UserDictWord {
+        surface: surface.to_string(),
+        pronunciation: hiragana_to_katakana(reading),
+        accent_type: 0,
+        word_type,
+        priority: DEFAULT_USER_DICT_PRIORITY,
+    })
+}
+
+fn decode_text(bytes: &[u8]) -> Option<String> {
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|chunk| from_bytes([chunk[0], chunk[1]]))
+            .collect();
+        String::from_utf16(&units).ok()
+    };
+    match bytes {
+        [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+        bytes => String::from_utf8(bytes.to_vec()).ok(),
+    }
+}
+
+fn katakana_to_hiragana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap(),
+            c => c,
+        })
+        .collect()
+}
@@ -0,0 +1,126 @@
+use crate::{user_dict::to_full_width, Result, UserDict, UserDictWord, UserDictWordType, Uuid};
+use std::collections::HashMap;
+
+/// `(元の単語, 追加する単語)`から、使う単語を決める関数。
+pub type UserDictMergeResolver = Box<dyn FnMut(&UserDictWord, &UserDictWord) -> UserDictWord>;
+
+/// 衝突した単語の解決方法。
+pub enum UserDictMergePolicy {
+    /// 元の単語を残す。
+    KeepExisting,
+    /// 追加する単語で置き換える。
+    TakeIncoming,
+    /// 優先度の高い方を使う。同じ場合は元の単語を残す。
+    HigherPriority,
+    /// `(元の単語, 追加する単語)`から、使う単語を決める。
+    Custom(UserDictMergeResolver),
+}
+
+/// [`UserDict::merge`]のオプション。
+pub struct UserDictMergeOptions {
+    /// 表記に加えて単語の種類も同じ場合にだけ衝突とみなすかどうか。
+    pub by_word_type: bool,
+    /// 衝突した単語の解決方法。
+    pub policy: UserDictMergePolicy,
+}
+
+impl Default for UserDictMergeOptions {
+    fn default() -> Self {
+        Self {
+            by_word_type: false,
+            policy: UserDictMergePolicy::KeepExisting,
+        }
+    }
+}
+
+/// 衝突した単語。
+#[derive(Debug, Clone)]
+pub struct UserDictMergeConflict {
+    /// 元の辞書での単語のUUID。
+    pub uuid: Uuid,
+    /// 元の単語。
+    pub existing: UserDictWord,
+    /// 追加しようとした単語。
+    pub incoming: UserDictWord,
+    /// 解決した結果の単語。
+    pub resolved: UserDictWord,
+}
+
+/// [`UserDict::merge`]の結果。
+#[derive(Debug, Clone, Default)]
+pub struct UserDictMergeReport {
+    /// 衝突せずに追加した単語のUUID。
+    pub added: Vec<Uuid>,
+    /// 衝突して、元の単語を書き換えたもの。
+    pub updated: Vec<UserDictMergeConflict>,
+    /// 衝突して、元の単語をそのまま残したもの。
+    pub kept: Vec<UserDictMergeConflict>,
+    /// 元の単語とまったく同じだったので無視した単語の数。
+    pub identical: usize,
+}
+
+type ConflictKey = (String, Option<UserDictWordType>);
+
+impl UserDict {
+    /// 他のユーザー辞書の単語を、表記の衝突を解決しながら追加する。
+    ///
+    /// [`UserDict::import`]と違い、同じ表記の単語が既にある場合は`options.policy`に従ってどちらか
+    /// を残す。表記は半角と全角を区別せずに比べる。元の辞書に同じ表記の単語が複数ある場合は、最初の
+    /// ものと比べる。
+    pub fn merge(
+        &self,
+        other: &UserDict,
+        mut options: UserDictMergeOptions,
+    ) -> Result<UserDictMergeReport> {
+        let key = |word: &UserDictWord| -> ConflictKey {
+            (
+                to_full_width(&word.surface),
+                options.by_word_type.then_some(word.word_type),
+            )
+        };
+        let mut index: HashMap<ConflictKey, (Uuid, UserDictWord)> = HashMap::new();
+        for (uuid, word) in self.iter()? {
+            index.entry(key(&word)).or_insert((uuid, word));
+        }
+
+        let mut report = UserDictMergeReport::default();
+        for (_, incoming) in other.iter()? {
+            let key = key(&incoming);
+            let Some((uuid, existing)) = index.get(&key).cloned() else {
+                let uuid = self.add_word(incoming.clone())?;
+                report.added.push(uuid);
+                index.insert(key, (uuid, incoming));
+                continue;
+            };
+            if existing == incoming {
+                report.identical += 1;
+                continue;
+            }
+
+            let resolved = match &mut options.policy {
+                UserDictMergePolicy::KeepExisting => existing.clone(),
+                UserDictMergePolicy::TakeIncoming => incoming.clone(),
+                UserDictMergePolicy::HigherPriority if incoming.priority > existing.priority => {
+                    incoming.clone()
+                }
+                UserDictMergePolicy::HigherPriority => existing.clone(),
+                UserDictMergePolicy::Custom(resolve) => resolve(&existing, &incoming),
+            };
+            let conflict = UserDictMergeConflict {
+                uuid,
+                existing,
+                incoming,
+                resolved: resolved.clone(),
+            };
+            if conflict.resolved == conflict.existing {
+                report.kept.push(conflict);
+            } else {
+                self.update_word(uuid, resolved.clone())?;
+                index.insert(key, (uuid, resolved));
+                report.updated.push(conflict);
+            }
+        }
+
+        Ok(report)
+    }
+}
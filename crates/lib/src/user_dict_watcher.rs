@@ -0,0 +1,142 @@
+use crate::{OpenJtalkRc, Result, UserDict, VoicevoxError};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// [`UserDictWatcher`]の再読み込みの結果。
+#[derive(Debug)]
+pub enum UserDictReloadEvent {
+    /// 読み込みなおして`OpenJtalkRc`に適用した。
+    Reloaded {
+        /// 読み込んだ単語の数。
+        words: usize,
+    },
+    /// 読み込みに失敗した。前の辞書をそのまま使う。
+    Failed(VoicevoxError),
+}
+
+/// ファイルの更新を判断するための、更新日時と大きさ。ファイルが無い場合は`None`。
+type FileSignature = Option<(Option<SystemTime>, u64)>;
+
+/// 再読み込みの結果を受け取る関数。
+type ReloadCallback = Box<dyn FnMut(&UserDictReloadEvent)>;
+
+fn file_signature(path: &Path) -> FileSignature {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+/// ユーザー辞書のJSONファイルを監視し、更新されたら読み込みなおして`OpenJtalkRc`に適用する。
+///
+/// `OpenJtalkRc`はスレッド間で共有できないので、バックグラウンドでは監視しない。
+/// 読み上げのループなどから[`UserDictWatcher::poll`]を定期的に呼ぶ。
+pub struct UserDictWatcher {
+    path: PathBuf,
+    user_dict: UserDict,
+    debounce: Duration,
+    signature: FileSignature,
+    /// 変更を見つけたときのファイルの状態と、その状態になった時刻。
+    pending: Option<(FileSignature, Instant)>,
+    callback: Option<ReloadCallback>,
+}
+
+impl UserDictWatcher {
+    /// ファイルを読み込んで`open_jtalk`に適用し、監視を始める。
+    ///
+    /// 変更が落ち着くまで待つ時間は0.5秒になる。
+    pub fn new<S: AsRef<Path>>(path: S, open_jtalk: &OpenJtalkRc) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let signature = file_signature(&path);
+        let user_dict = UserDict::new()?;
+        user_dict.load(&path)?;
+        open_jtalk.use_user_dict(&user_dict)?;
+
+        Ok(Self {
+            path,
+            user_dict,
+            debounce: Duration::from_millis(500),
+            signature,
+            pending: None,
+            callback: None,
+        })
+    }
+
+    /// 変更が落ち着くまで待つ時間を設定する。
+    ///
+    /// エディタの保存などでファイルが何度も書き換えられる間は読み込まず、
+    /// ファイルがこの時間だけ変わらなかったときに読み込む。
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        Self { debounce, ..self }
+    }
+
+    /// 再読み込みしたとき、または失敗したときに呼ぶ関数を設定する。
+    pub fn on_reload(self, callback: impl FnMut(&UserDictReloadEvent) + 'static) -> Self {
+        Self {
+            callback: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    /// 監視しているファイルのパス。
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 今`OpenJtalkRc`に適用しているユーザー辞書。
+    pub fn user_dict(&self) -> &UserDict {
+        &self.user_dict
+    }
+
+    /// ファイルが更新されていて、変更が落ち着いていれば読み込みなおして`open_jtalk`に適用する。
+    ///
+    /// 読み込みに失敗した場合は前の辞書をそのまま使い、ファイルがもう一度更新されるまで読み込まない。
+    ///
+    /// # Returns
+    ///
+    /// 読み込みを試した場合はその結果。コールバックにも同じ結果を渡す。
+    pub fn poll(&mut self, open_jtalk: &OpenJtalkRc) -> Option<UserDictReloadEvent> {
+        let signature = file_signature(&self.path);
+        if signature == self.signature {
+            self.pending = None;
+            return None;
+        }
+
+        let now = Instant::now();
+        match self.pending {
+            Some((pending, since)) if pending == signature => {
+                if now.duration_since(since) < self.debounce {
+                    return None;
+                }
+            }
+            _ => {
+                self.pending = Some((signature, now));
+                if !self.debounce.is_zero() {
+                    return None;
+                }
+            }
+        }
+        self.pending = None;
+        self.signature = signature;
+
+        let event = match self.reload(open_jtalk) {
+            Ok(words) => UserDictReloadEvent::Reloaded { words },
+            Err(err) => UserDictReloadEvent::Failed(err),
+        };
+        if let Some(callback) = &mut self.callback {
+            callback(&event);
+        }
+
+        Some(event)
+    }
+
+    fn reload(&mut self, open_jtalk: &OpenJtalkRc) -> Result<usize> {
+        let user_dict = UserDict::new()?;
+        user_dict.load(&self.path)?;
+        open_jtalk.use_user_dict(&user_dict)?;
+        let words = user_dict.len()?;
+        self.user_dict = user_dict;
+
+        Ok(words)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{user_dict::hiragana_to_katakana, UserDictWord, UserDictWordType};
+use thiserror::Error;
+
+/// ユーザー辞書の単語の優先度の最小値。
+pub const MIN_USER_DICT_PRIORITY: u32 = 0;
+/// ユーザー辞書の単語の優先度の最大値。
+pub const MAX_USER_DICT_PRIORITY: u32 = 10;
+/// ユーザー辞書の単語の優先度の既定値。
+pub const DEFAULT_USER_DICT_PRIORITY: u32 = 5;
+
+/// 前の文字と合わせて1モーラになる小書きのカナ。
+const SMALL_KANA: &[char] = &['ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ヮ'];
+
+/// ユーザー辞書の単語の検証のエラー。
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum UserDictWordError {
+    /// 表記が空
+    #[error("表記が空")]
+    EmptySurface,
+
+    /// 読みが空
+    #[error("読みが空")]
+    EmptyPronunciation,
+
+    /// 読みにカタカナ以外の文字がある
+    #[error("読みの{position}文字目の「{character}」がカタカナではない")]
+    NotKatakana {
+        /// 文字。
+        character: char,
+        /// 1から始まる文字の位置。
+        position: usize,
+    },
+
+    /// 読みの小書きのカナの位置が不正
+    #[error("読みの{position}文字目の「{character}」の前に、合わせて1モーラになるカナが無い")]
+    MisplacedSmallKana {
+        /// 文字。
+        character: char,
+        /// 1から始まる文字の位置。
+        position: usize,
+    },
+
+    /// 読みが長音や促音から始まる
+    #[error("読みが「{0}」から始まっている")]
+    InvalidFirstCharacter(char),
+
+    /// アクセント型がモーラ数を超えている
+    #[error("アクセント型{accent_type}がモーラ数{mora_count}を超えている")]
+    AccentTypeOutOfRange {
+        /// アクセント型。
+        accent_type: usize,
+        /// 読みのモーラ数。
+        mora_count: usize,
+    },
+
+    /// 優先度が範囲外
+    #[error("優先度{0}が{MIN_USER_DICT_PRIORITY}から{MAX_USER_DICT_PRIORITY}の範囲外")]
+    PriorityOutOfRange(u32),
+}
+
+/// カタカナの読みのモーラ数を数える。
+///
+/// `ャュョ`などの小書きのカナは前の文字と合わせて1モーラとし、`ッ`・`ン`・`ー`はそれぞれ1モーラとする。
+pub fn count_moras(pronunciation: &str) -> usize {
+    pronunciation
+        .chars()
+        .filter(|c| !SMALL_KANA.contains(c))
+        .count()
+}
+
+/// 読みを検証する。ひらがなはカタカナにしてから検証し、カタカナにした読みを返す。
+pub fn validate_pronunciation(pronunciation: &str) -> Result<String, UserDictWordError> {
+    let pronunciation = hiragana_to_katakana(pronunciation);
+    let mut previous = None;
+    for (position, character) in (1..).zip(pronunciation.chars()) {
+        if !matches!(character, 'ァ'..='ヴ' | 'ー') {
+            return Err(UserDictWordError::NotKatakana {
+                character,
+                position,
+            });
+        }
+        if position == 1 && matches!(character, 'ー' | 'ッ') {
+            return Err(UserDictWordError::InvalidFirstCharacter(character));
+        }
+        if SMALL_KANA.contains(&character)
+            && previous.is_none_or(|previous: char| {
+                SMALL_KANA.contains(&previous) || matches!(previous, 'ッ' | 'ン' | 'ー')
+            })
+        {
+            return Err(UserDictWordError::MisplacedSmallKana {
+                character,
+                position,
+            });
+        }
+        previous = Some(character);
+    }
+    if previous.is_none() {
+        return Err(UserDictWordError::EmptyPronunciation);
+    }
+
+    Ok(pronunciation)
+}
+
+/// [`UserDictWord`]のビルダー。[`UserDictWordBuilder::build`]で内容を検証する。
+#[derive(Debug, Clone)]
+pub struct UserDictWordBuilder {
+    surface: String,
+    pronunciation: String,
+    accent_type: usize,
+    word_type: UserDictWordType,
+    priority: u32,
+}
+
+impl UserDictWord {
+    /// ビルダーを作成する。単語の種類は固有名詞、アクセント型は0、優先度は5になる。
+    pub fn builder(surface: &str, pronunciation: &str) -> UserDictWordBuilder {
+        UserDictWordBuilder {
+            surface: surface.to_string(),
+            pronunciation: pronunciation.to_string(),
+            accent_type: 0,
+            word_type: UserDictWordType::ProperNoun,
+            priority: DEFAULT_USER_DICT_PRIORITY,
+        }
+    }
+}
+
+impl UserDictWordBuilder {
+    /// アクセント型を設定する。
+    pub fn accent_type(mut self, accent_type: usize) -> Self {
+        self.accent_type = accent_type;
+        self
+    }
+
+    /// 単語の種類を設定する。
+    pub fn word_type(mut self, word_type: UserDictWordType) -> Self {
+        self.word_type = word_type;
+        self
+    }
+
+    /// 優先度を設定する。
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 内容を検証して単語を作成する。
+    pub fn build(self) -> Result<UserDictWord, UserDictWordError> {
+        if self.surface.trim().is_empty() {
+            return Err(UserDictWordError::EmptySurface);
+        }
+        let pronunciation = validate_pronunciation(&self.pronunciation)?;
+        let mora_count = count_moras(&pronunciation);
+        if self.accent_type > mora_count {
+            return Err(UserDictWordError::AccentTypeOutOfRange {
+                accent_type: self.accent_type,
+                mora_count,
+            });
+        }
+        if !(MIN_USER_DICT_PRIORITY..=MAX_USER_DICT_PRIORITY).contains(&self.priority) {
+            return Err(UserDictWordError::PriorityOutOfRange(self.priority));
+        }
+
+        Ok(UserDictWord {
+            surface: self.surface,
+            pronunciation,
+            accent_type: self.accent_type,
+            word_type: self.word_type,
+            priority: self.priority,
+        })
+    }
+}
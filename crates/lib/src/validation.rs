@@ -0,0 +1,350 @@
+use crate::{AccentPhrase, AudioQuery, MoraModel};
+use std::fmt;
+use thiserror::Error;
+
+/// 音声合成のデフォルトのサンプリングレート。
+///
+/// 出力サンプリングレートはこの値の整数倍でなければならない。
+pub const DEFAULT_SAMPLING_RATE: u32 = 24000;
+
+/// 母音として使える音素。
+pub const VOWEL_PHONEMES: &[&str] = &[
+    "a", "i", "u", "e", "o", "A", "I", "U", "E", "O", "N", "cl", "pau",
+];
+
+/// 子音として使える音素。
+pub const CONSONANT_PHONEMES: &[&str] = &[
+    "b", "by", "ch", "d", "dy", "f", "g", "gw", "gy", "h", "hy", "j", "k", "kw", "ky", "m", "my",
+    "n", "ny", "p", "py", "r", "ry", "s", "sh", "t", "ts", "ty", "v", "w", "y", "z",
+];
+
+/// AudioQuery内の位置。インデックスは0始まり。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryLocation {
+    /// AudioQuery本体。
+    Query,
+    /// アクセント句。
+    AccentPhrase { phrase: usize },
+    /// アクセント句内のモーラ。
+    Mora { phrase: usize, mora: usize },
+    /// アクセント句の後ろの無音モーラ。
+    PauseMora { phrase: usize },
+}
+
+impl fmt::Display for QueryLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryLocation::Query => write!(f, "audio_query"),
+            QueryLocation::AccentPhrase { phrase } => write!(f, "accent_phrases[{}]", phrase),
+            QueryLocation::Mora { phrase, mora } => {
+                write!(f, "accent_phrases[{}].moras[{}]", phrase, mora)
+            }
+            QueryLocation::PauseMora { phrase } => {
+                write!(f, "accent_phrases[{}].pause_mora", phrase)
+            }
+        }
+    }
+}
+
+/// AudioQueryの問題の種類。
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum QueryIssueKind {
+    /// 値がNaNまたは無限大。
+    #[error("{field}が有限の値ではない（{value}）")]
+    NotFinite { field: &'static str, value: f32 },
+
+    /// 値が負。
+    #[error("{field}が負の値（{value}）")]
+    Negative { field: &'static str, value: f32 },
+
+    /// 値が0以下。
+    #[error("{field}が0以下（{value}）")]
+    NotPositive { field: &'static str, value: f32 },
+
+    /// アクセント句にモーラが無い。
+    #[error("モーラが空")]
+    EmptyAccentPhrase,
+
+    /// アクセント位置がモーラの範囲外。
+    #[error("アクセント位置{accent}が1から{mora_count}の範囲外")]
+    AccentOutOfRange { accent: usize, mora_count: usize },
+
+    /// 未知の音素。
+    #[error("{field}の音素`{phoneme}`は未知の音素")]
+    UnknownPhoneme {
+        field: &'static str,
+        phoneme: String,
+    },
+
+    /// 子音と子音の音長の片方だけが指定されている。
+    #[error("consonantとconsonant_lengthの有無が一致しない")]
+    ConsonantMismatch,
+
+    /// サポートされていないサンプリングレート。
+    #[error("サンプリングレート{0}はサポートされていない（{DEFAULT_SAMPLING_RATE}の整数倍のみ）")]
+    UnsupportedSamplingRate(u32),
+}
+
+impl QueryIssueKind {
+    /// [`AudioQuery::repair`]で修正できるかどうか。
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, QueryIssueKind::UnknownPhoneme { .. })
+    }
+}
+
+/// [`AudioQuery::validate`]で見つかった問題。
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{location}: {kind}")]
+pub struct QueryIssue {
+    /// 問題のある位置。
+    pub location: QueryLocation,
+    /// 問題の種類。
+    pub kind: QueryIssueKind,
+}
+
+impl AudioQuery {
+    /// AudioQueryを検証する。
+    ///
+    /// 問題が見つかった場合は、見つかった全ての問題を位置付きで返す。
+    pub fn validate(&self) -> std::result::Result<(), Vec<QueryIssue>> {
+        let mut issues = vec![];
+        let location = QueryLocation::Query;
+
+        check_finite(&mut issues, location, "speed_scale", self.speed_scale);
+        if self.speed_scale.is_finite() && self.speed_scale <= 0.0 {
+            issues.push(QueryIssue {
+                location,
+                kind: QueryIssueKind::NotPositive {
+                    field: "speed_scale",
+                    value: self.speed_scale,
+                },
+            });
+        }
+        check_finite(&mut issues, location, "pitch_scale", self.pitch_scale);
+        check_finite(
+            &mut issues,
+            location,
+            "intonation_scale",
+            self.intonation_scale,
+        );
+        check_length(&mut issues, location, "volume_scale", self.volume_scale);
+        check_length(
+            &mut issues,
+            location,
+            "pre_phoneme_length",
+            self.pre_phoneme_length,
+        );
+        check_length(
+            &mut issues,
+            location,
+            "post_phoneme_length",
+            self.post_phoneme_length,
+        );
+        if !is_supported_sampling_rate(self.output_sampling_rate) {
+            issues.push(QueryIssue {
+                location,
+                kind: QueryIssueKind::UnsupportedSamplingRate(self.output_sampling_rate),
+            });
+        }
+
+        for (index, accent_phrase) in self.accent_phrases.iter().enumerate() {
+            validate_accent_phrase(&mut issues, index, accent_phrase);
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// AudioQueryの問題を可能な範囲で修正する。
+    ///
+    /// NaNや負の値は0に、アクセント位置はモーラの範囲内に、サンプリングレートは最も近い対応値に丸め
+    /// る。モーラが空のアクセント句は取り除く。修正後に残った問題（未知の音素など）を返す。
+    pub fn repair(&mut self) -> std::result::Result<(), Vec<QueryIssue>> {
+        if !self.speed_scale.is_finite() || self.speed_scale <= 0.0 {
+            self.speed_scale = 1.0;
+        }
+        if !self.pitch_scale.is_finite() {
+            self.pitch_scale = 0.0;
+        }
+        if !self.intonation_scale.is_finite() {
+            self.intonation_scale = 1.0;
+        }
+        repair_length(&mut self.volume_scale);
+        repair_length(&mut self.pre_phoneme_length);
+        repair_length(&mut self.post_phoneme_length);
+        if !is_supported_sampling_rate(self.output_sampling_rate) {
+            let multiple = (self.output_sampling_rate as f64 / DEFAULT_SAMPLING_RATE as f64)
+                .round()
+                .max(1.0) as u32;
+            self.output_sampling_rate = multiple * DEFAULT_SAMPLING_RATE;
+        }
+
+        self.accent_phrases
+            .retain(|accent_phrase| !accent_phrase.moras.is_empty());
+        for accent_phrase in &mut self.accent_phrases {
+            accent_phrase.accent = accent_phrase.accent.clamp(1, accent_phrase.moras.len());
+            for mora in &mut accent_phrase.moras {
+                repair_mora(mora);
+            }
+            if let Some(pause_mora) = &mut accent_phrase.pause_mora {
+                repair_mora(pause_mora);
+            }
+        }
+
+        self.validate()
+    }
+}
+
+/// AccentPhraseの配列を検証する。
+///
+/// [`AudioQuery::validate`]のうち、アクセント句に関する検証のみを行う。
+pub fn validate_accent_phrases(
+    accent_phrases: &[AccentPhrase],
+) -> std::result::Result<(), Vec<QueryIssue>> {
+    let mut issues = vec![];
+    for (index, accent_phrase) in accent_phrases.iter().enumerate() {
+        validate_accent_phrase(&mut issues, index, accent_phrase);
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+fn is_supported_sampling_rate(sampling_rate: u32) -> bool {
+    sampling_rate != 0 && sampling_rate.is_multiple_of(DEFAULT_SAMPLING_RATE)
+}
+
+fn validate_accent_phrase(
+    issues: &mut Vec<QueryIssue>,
+    index: usize,
+    accent_phrase: &AccentPhrase,
+) {
+    let mora_count = accent_phrase.moras.len();
+    if mora_count == 0 {
+        issues.push(QueryIssue {
+            location: QueryLocation::AccentPhrase { phrase: index },
+            kind: QueryIssueKind::EmptyAccentPhrase,
+        });
+    } else if accent_phrase.accent == 0 || accent_phrase.accent > mora_count {
+        issues.push(QueryIssue {
+            location: QueryLocation::AccentPhrase { phrase: index },
+            kind: QueryIssueKind::AccentOutOfRange {
+                accent: accent_phrase.accent,
+                mora_count,
+            },
+        });
+    }
+
+    for (mora_index, mora) in accent_phrase.moras.iter().enumerate() {
+        validate_mora(
+            issues,
+            QueryLocation::Mora {
+                phrase: index,
+                mora: mora_index,
+            },
+            mora,
+        );
+    }
+    if let Some(pause_mora) = &accent_phrase.pause_mora {
+        validate_mora(
+            issues,
+            QueryLocation::PauseMora { phrase: index },
+            pause_mora,
+        );
+    }
+}
+
+fn validate_mora(issues: &mut Vec<QueryIssue>, location: QueryLocation, mora: &MoraModel) {
+    check_finite(issues, location, "pitch", mora.pitch);
+    if mora.pitch.is_finite() && mora.pitch < 0.0 {
+        issues.push(QueryIssue {
+            location,
+            kind: QueryIssueKind::Negative {
+                field: "pitch",
+                value: mora.pitch,
+            },
+        });
+    }
+    check_length(issues, location, "vowel_length", mora.vowel_length);
+    if !VOWEL_PHONEMES.contains(&mora.vowel.as_str()) {
+        issues.push(QueryIssue {
+            location,
+            kind: QueryIssueKind::UnknownPhoneme {
+                field: "vowel",
+                phoneme: mora.vowel.clone(),
+            },
+        });
+    }
+
+    match (&mora.consonant, mora.consonant_length) {
+        (Some(consonant), Some(consonant_length)) => {
+            if !CONSONANT_PHONEMES.contains(&consonant.as_str()) {
+                issues.push(QueryIssue {
+                    location,
+                    kind: QueryIssueKind::UnknownPhoneme {
+                        field: "consonant",
+                        phoneme: consonant.clone(),
+                    },
+                });
+            }
+            check_length(issues, location, "consonant_length", consonant_length);
+        }
+        (None, None) => {}
+        _ => issues.push(QueryIssue {
+            location,
+            kind: QueryIssueKind::ConsonantMismatch,
+        }),
+    }
+}
+
+fn check_finite(
+    issues: &mut Vec<QueryIssue>,
+    location: QueryLocation,
+    field: &'static str,
+    value: f32,
+) {
+    if !value.is_finite() {
+        issues.push(QueryIssue {
+            location,
+            kind: QueryIssueKind::NotFinite { field, value },
+        });
+    }
+}
+
+fn check_length(
+    issues: &mut Vec<QueryIssue>,
+    location: QueryLocation,
+    field: &'static str,
+    value: f32,
+) {
+    check_finite(issues, location, field, value);
+    if value.is_finite() && value < 0.0 {
+        issues.push(QueryIssue {
+            location,
+            kind: QueryIssueKind::Negative { field, value },
+        });
+    }
+}
+
+fn repair_length(value: &mut f32) {
+    if !value.is_finite() || *value < 0.0 {
+        *value = 0.0;
+    }
+}
+
+fn repair_mora(mora: &mut MoraModel) {
+    repair_length(&mut mora.pitch);
+    repair_length(&mut mora.vowel_length);
+    match (&mora.consonant, &mut mora.consonant_length) {
+        (Some(_), Some(consonant_length)) => repair_length(consonant_length),
+        (Some(_), None) => mora.consonant_length = Some(0.0),
+        (None, Some(_)) => mora.consonant_length = None,
+        (None, None) => {}
+    }
+}
@@ -1,10 +1,12 @@
-use crate::{i32_to_result, Result, SpeakerMeta};
-use std::{ffi::CString, mem::MaybeUninit, path::Path};
+use crate::{asset::StagedPath, i32_to_result, AssetLoadError, Result, SpeakerMeta};
+use std::{ffi::CString, io::Read, mem::MaybeUninit, path::Path};
 use voicevox_core_sys as sys;
 
 /// 音声モデル。VVMファイルと対応する。
 pub struct VoiceModel {
     pub(crate) inner: *mut sys::VoicevoxVoiceModel,
+    /// [`VoiceModel::from_bytes`]などで書き出した一時ファイル。`inner`より後に破棄する。
+    _staged: Option<StagedPath>,
 }
 
 impl VoiceModel {
@@ -22,7 +24,30 @@ impl VoiceModel {
             ptr.assume_init()
         };
 
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            _staged: None,
+        })
+    }
+
+    /// メモリ上のVVMファイルの内容から音声モデルを読み込む。
+    ///
+    /// C APIはパスしか受け付けないので、内容を一時ファイルに書き出して読み込む。一時ファイルは音声
+    /// モデルを破棄するときに削除する。
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, AssetLoadError> {
+        Self::from_reader(bytes)
+    }
+
+    /// ネットワークや暗号化されたアーカイブなどから読み出したVVMファイルの内容から、音声モデルを
+    /// 読み込む。
+    ///
+    /// [`VoiceModel::from_bytes`]と同じく一時ファイルを使う。
+    pub fn from_reader<R: Read>(mut reader: R) -> std::result::Result<Self, AssetLoadError> {
+        let staged = StagedPath::file(&mut reader, "vvm")?;
+        let mut model = Self::from_path(staged.path())?;
+        model._staged = Some(staged);
+
+        Ok(model)
     }
 
     /// メタ情報を取得する。
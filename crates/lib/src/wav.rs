@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+/// PCM形式のWAVデータ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Wav {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+    pub data: Vec<u8>,
+}
+
+impl Wav {
+    /// WAV形式のバイト列を読み込む。
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return None;
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let id = &bytes[offset..offset + 4];
+            let size =
+                u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+            let body = bytes.get(offset + 8..offset + 8 + size)?;
+            match id {
+                b"fmt " if size >= 16 => {
+                    let channels = u16::from_le_bytes(body[2..4].try_into().unwrap());
+                    let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                    let bits_per_sample = u16::from_le_bytes(body[14..16].try_into().unwrap());
+                    format = Some((channels, sample_rate, bits_per_sample));
+                }
+                b"data" => data = Some(body.to_vec()),
+                _ => {}
+            }
+            // チャンクは2バイト境界に揃えられる。
+            offset += 8 + size + size % 2;
+        }
+
+        let (channels, sample_rate, bits_per_sample) = format?;
+        Some(Self {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            data: data?,
+        })
+    }
+
+    /// 同じ形式の空のWAVを作る。
+    pub fn empty_like(&self) -> Self {
+        Self {
+            data: vec![],
+            ..self.clone()
+        }
+    }
+
+    fn block_align(&self) -> usize {
+        self.channels as usize * self.bits_per_sample as usize / 8
+    }
+
+    /// 音声の長さ。
+    pub fn duration(&self) -> Duration {
+        let frames = self.data.len() / self.block_align();
+        Duration::from_secs_f64(frames as f64 / self.sample_rate as f64)
+    }
+
+    /// 無音を末尾に追加する。
+    pub fn push_silence(&mut self, duration: Duration) {
+        let frames = (duration.as_secs_f64() * self.sample_rate as f64).round() as usize;
+        self.data
+            .resize(self.data.len() + frames * self.block_align(), 0);
+    }
+
+    /// 同じ形式のWAVを末尾に追加する。形式が異なる場合は`false`を返す。
+    pub fn append(&mut self, other: &Wav) -> bool {
+        if (self.channels, self.sample_rate, self.bits_per_sample)
+            != (other.channels, other.sample_rate, other.bits_per_sample)
+        {
+            return false;
+        }
+        self.data.extend_from_slice(&other.data);
+
+        true
+    }
+
+    /// WAV形式のバイト列に変換する。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let block_align = self.block_align() as u16;
+        let byte_rate = self.sample_rate * block_align as u32;
+
+        let mut bytes = Vec::with_capacity(44 + self.data.len());
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + self.data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&byte_rate.to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&self.bits_per_sample.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+
+        bytes
+    }
+}
@@ -0,0 +1,53 @@
+//! ユーザー辞書の単語をネイティブの形式に変換するときに、メモリが漏れないことを確かめる。
+//!
+//! ネイティブのライブラリは自前のアロケータを使うので、このクレートが確保したメモリだけを数える。
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+use voicevox_core_rs as vv;
+
+/// スレッドごとに、確保したまま解放していないバイト数を数えるアロケータ。
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.with(|allocated| allocated.set(allocated.get() + layout.size() as isize));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.with(|allocated| allocated.set(allocated.get() - layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocated() -> isize {
+    ALLOCATED.with(Cell::get)
+}
+
+#[test]
+fn test_user_dict_word_conversion_does_not_leak() {
+    let dict = vv::UserDict::new().unwrap();
+    let word = vv::UserDictWord::new("リークテスト", "リークテスト");
+    let uuid = dict.add_word(word.clone()).unwrap();
+
+    let before = allocated();
+    for _ in 0..1000 {
+        let uuid = dict.add_word(word.clone()).unwrap();
+        dict.update_word(uuid, word.clone()).unwrap();
+        dict.update_word(uuid, word.clone()).unwrap();
+    }
+    dict.update_word(uuid, word.clone()).unwrap();
+    let after = allocated();
+
+    assert_eq!(after - before, 0);
+}
@@ -59,3 +59,2209 @@ fn test_dict() {
 
     assert_ne!(before_kana, after_kana);
 }
+
+#[test]
+fn test_validate_audio_query() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let mut audio_query = synthesizer
+        .create_audio_query("ハローワールド", style_id)
+        .unwrap();
+    audio_query.validate().unwrap();
+
+    audio_query.accent_phrases[0].accent = 0;
+    audio_query.accent_phrases[0].moras[1].pitch = f32::NAN;
+    audio_query.accent_phrases[0].moras[1].vowel_length = -0.1;
+    audio_query.output_sampling_rate = 44100;
+    let issues = audio_query.validate().unwrap_err();
+
+    assert_eq!(issues.len(), 4);
+    assert!(issues.iter().any(|issue| issue.location
+        == vv::QueryLocation::Mora { phrase: 0, mora: 1 }
+        && matches!(
+            issue.kind,
+            vv::QueryIssueKind::Negative {
+                field: "vowel_length",
+                ..
+            }
+        )));
+
+    audio_query.repair().unwrap();
+    assert_eq!(audio_query.accent_phrases[0].accent, 1);
+    assert_eq!(audio_query.output_sampling_rate, 48000);
+    synthesizer
+        .synthesis(&audio_query, style_id, Default::default())
+        .unwrap();
+
+    audio_query.accent_phrases[0].moras[0].vowel = "x".to_string();
+    let issues = audio_query.repair().unwrap_err();
+    assert!(!issues[0].kind.is_repairable());
+}
+
+#[test]
+fn test_kana_round_trip() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    for text in [
+        "ハローワールド",
+        "こんにちは、今日はいい天気ですね。",
+        "本当にそう思いますか？",
+        "明日、東京駅で待ち合わせしましょう。",
+    ] {
+        let audio_query = synthesizer.create_audio_query(text, style_id).unwrap();
+        let kana = audio_query.kana.unwrap();
+
+        let accent_phrases = vv::parse_kana(&kana).unwrap();
+        assert_eq!(vv::create_kana(&accent_phrases), kana);
+        assert_eq!(vv::create_kana(&audio_query.accent_phrases), kana);
+        for (parsed, expected) in accent_phrases.iter().zip(&audio_query.accent_phrases) {
+            assert_eq!(parsed.accent, expected.accent);
+            assert_eq!(parsed.is_interrogative, expected.is_interrogative);
+            assert_eq!(parsed.pause_mora.is_some(), expected.pause_mora.is_some());
+            let texts = |accent_phrase: &vv::AccentPhrase| {
+                accent_phrase
+                    .moras
+                    .iter()
+                    .map(|mora| (mora.text.clone(), mora.vowel.clone()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(texts(parsed), texts(expected));
+        }
+    }
+}
+
+#[test]
+fn test_kana_parse_error() {
+    let error = vv::parse_kana("ア'イ/ウエ").unwrap_err();
+    assert_eq!(error.position, 6);
+    assert_eq!(error.kind, vv::KanaParseErrorKind::MissingAccent);
+
+    let error = vv::parse_kana("ア'イ、'ウエ").unwrap_err();
+    assert_eq!(error.position, 4);
+    assert_eq!(error.kind, vv::KanaParseErrorKind::AccentAtStart);
+
+    let error = vv::parse_kana("ア'イ？ウ").unwrap_err();
+    assert_eq!(error.position, 3);
+    assert_eq!(error.kind, vv::KanaParseErrorKind::InterrogativeNotAtEnd);
+
+    let error = vv::parse_kana("ア'xイ").unwrap_err();
+    assert_eq!(error.position, 2);
+    assert_eq!(
+        error.kind,
+        vv::KanaParseErrorKind::UnknownText("x".to_string())
+    );
+
+    let error = vv::parse_kana("ア'イ//ウ'").unwrap_err();
+    assert_eq!(error.position, 4);
+    assert_eq!(error.kind, vv::KanaParseErrorKind::EmptyAccentPhrase);
+
+    let error = vv::parse_kana("_ン'").unwrap_err();
+    assert_eq!(error.position, 0);
+    assert_eq!(error.kind, vv::KanaParseErrorKind::InvalidUnvoice);
+}
+
+#[test]
+fn test_kana_fuzz() {
+    let mut state = 0x2545f4914f6cdd1du64;
+    let mut next = move |max: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % max as u64) as usize
+    };
+
+    for _ in 0..1000 {
+        let mut kana = String::new();
+        for phrase_index in 0..next(4) + 1 {
+            if phrase_index > 0 {
+                kana.push(if next(2) == 0 { '/' } else { '、' });
+            }
+            let mora_count = next(6) + 1;
+            let accent = next(mora_count) + 1;
+            for mora_index in 0..mora_count {
+                let (text, _, vowel) = vv::MORA_LIST[next(vv::MORA_LIST.len())];
+                if "aiueo".contains(vowel) && next(4) == 0 {
+                    kana.push('_');
+                }
+                kana.push_str(text);
+                if mora_index + 1 == accent {
+                    kana.push('\'');
+                }
+            }
+            if next(4) == 0 {
+                kana.push('？');
+            }
+        }
+
+        let accent_phrases = vv::parse_kana(&kana).unwrap();
+        assert_eq!(vv::create_kana(&accent_phrases), kana);
+    }
+
+    let alphabet: Vec<char> = "アキャンッヴ_'/、？xー ".chars().collect();
+    for _ in 0..10000 {
+        let kana: String = (0..next(12))
+            .map(|_| alphabet[next(alphabet.len())])
+            .collect();
+        match vv::parse_kana(&kana) {
+            Ok(accent_phrases) => assert_eq!(vv::create_kana(&accent_phrases), kana),
+            Err(error) => assert!(error.position <= kana.chars().count()),
+        }
+    }
+}
+
+#[test]
+fn test_merge_edits() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let mut edited = synthesizer
+        .create_audio_query("今日はいい天気ですね。", style_id)
+        .unwrap();
+    edited.speed_scale = 1.5;
+    for mora in &mut edited.accent_phrases.last_mut().unwrap().moras {
+        mora.pitch += 1.0;
+    }
+
+    let fresh = synthesizer
+        .create_audio_query("明日はいい天気ですね。", style_id)
+        .unwrap();
+    let merged = edited.merge_edits(&fresh);
+
+    assert_eq!(merged.speed_scale, 1.5);
+    assert_eq!(merged.kana, fresh.kana);
+    assert_eq!(merged.accent_phrases.last(), edited.accent_phrases.last());
+    assert_eq!(merged.accent_phrases[0], fresh.accent_phrases[0]);
+    synthesizer
+        .synthesis(&merged, style_id, Default::default())
+        .unwrap();
+}
+
+#[test]
+fn test_preset() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let preset = vv::Preset {
+        name: "早口".to_string(),
+        style_id: Some(style_id),
+        speed_scale: 1.5,
+        output_sampling_rate: Some(48000),
+        ..Default::default()
+    };
+    assert_eq!(
+        vv::Preset::from_toml(&preset.to_toml().unwrap()).unwrap(),
+        preset
+    );
+    assert_eq!(
+        vv::Preset::from_json(&preset.to_json().unwrap()).unwrap(),
+        preset
+    );
+
+    let mut audio_query = synthesizer
+        .create_audio_query("ハローワールド", style_id)
+        .unwrap();
+    preset.apply(&mut audio_query);
+    assert_eq!(audio_query.speed_scale, 1.5);
+    assert_eq!(audio_query.output_sampling_rate, 48000);
+
+    synthesizer
+        .tts_with_preset("ハローワールド", style_id, &preset, Default::default())
+        .unwrap();
+
+    // プリセットに紐付けたスタイルIDが引数より優先される。
+    synthesizer
+        .tts_with_preset("ハローワールド", u32::MAX, &preset, Default::default())
+        .unwrap();
+}
+
+#[test]
+fn test_preset_from_editor_json() {
+    let json = r#"{
+        "presets": {
+            "items": {
+                "b": {
+                    "name": "ゆっくり",
+                    "speedScale": 0.8,
+                    "pitchScale": 0.0,
+                    "intonationScale": 1.0,
+                    "volumeScale": 1.0,
+                    "prePhonemeLength": 0.1,
+                    "postPhonemeLength": 0.1
+                },
+                "a": {
+                    "name": "高め",
+                    "speedScale": 1.0,
+                    "pitchScale": 0.1,
+                    "intonationScale": 1.2,
+                    "volumeScale": 1.0,
+                    "prePhonemeLength": 0.1,
+                    "postPhonemeLength": 0.3
+                }
+            },
+            "keys": ["a", "b"]
+        },
+        "defaultPresetKeys": {
+            "074fc39e-678b-4c13-8916-ffca8d505d1d:7ffcb7ce-00ec-4bdc-82cd-45a8889e43ff:3": "b"
+        }
+    }"#;
+
+    let presets = vv::Preset::from_editor_json(json).unwrap();
+    assert_eq!(presets.len(), 2);
+    assert_eq!(presets[0].name, "高め");
+    assert_eq!(presets[0].style_id, None);
+    assert_eq!(presets[0].post_phoneme_length, 0.3);
+    assert_eq!(presets[1].name, "ゆっくり");
+    assert_eq!(presets[1].style_id, Some(3));
+}
+
+#[test]
+fn test_parse_ssml() {
+    let ssml = r#"<speak>
+        こんにちは<break time="300ms"/>
+        <prosody rate="120%" pitch="+2st" volume="+6dB">
+            <sub alias="ボイスボックス">VOICEVOX</sub>です。
+            <prosody rate="0.5">ゆっくり</prosody>
+        </prosody>
+        <mark name="voice"/>
+        <voice name="angry"><say-as interpret-as="kana">オ'コッテ/イマ'ス</say-as></voice>
+    </speak>"#;
+    let options = vv::SsmlOptions {
+        voices: [("angry".to_string(), 5)].into(),
+        ..Default::default()
+    };
+
+    let segments = vv::parse_ssml(ssml, 0, &options).unwrap();
+    assert_eq!(segments.len(), 6);
+    assert!(matches!(
+        &segments[0],
+        vv::SsmlSegment::Speech { text, style_id: 0, .. } if text == "こんにちは"
+    ));
+    assert_eq!(
+        segments[1],
+        vv::SsmlSegment::Break(std::time::Duration::from_millis(300))
+    );
+    let vv::SsmlSegment::Speech { text, prosody, .. } = &segments[2] else {
+        panic!("unexpected segment: {:?}", segments[2]);
+    };
+    assert_eq!(text, "ボイスボックスです。");
+    assert_eq!(prosody.rate, 1.2);
+    assert!((prosody.pitch - std::f32::consts::LN_2 / 6.0).abs() < 1e-6);
+    assert!((prosody.volume - 1.995).abs() < 1e-3);
+    let vv::SsmlSegment::Speech { prosody, .. } = &segments[3] else {
+        panic!("unexpected segment: {:?}", segments[3]);
+    };
+    assert!((prosody.rate - 0.6).abs() < 1e-6);
+    assert_eq!(segments[4], vv::SsmlSegment::Mark("voice".to_string()));
+    assert!(matches!(
+        &segments[5],
+        vv::SsmlSegment::Speech {
+            is_kana: true,
+            style_id: 5,
+            ..
+        }
+    ));
+
+    assert!(matches!(
+        vv::parse_ssml("<speak><audio src='a.wav'/></speak>", 0, &options),
+        Err(vv::SsmlError::UnsupportedElement { .. })
+    ));
+    assert!(matches!(
+        vv::parse_ssml(
+            "<speak><voice name='unknown'>あ</voice></speak>",
+            0,
+            &options
+        ),
+        Err(vv::SsmlError::UnknownVoice { .. })
+    ));
+    assert!(matches!(
+        vv::parse_ssml(
+            "<speak><prosody rate='fastest'>あ</prosody></speak>",
+            0,
+            &options
+        ),
+        Err(vv::SsmlError::InvalidAttribute {
+            attribute: "rate",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_synthesis_ssml() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let ssml = r#"<speak>
+        ハロー<break time="1s"/><mark name="world"/><prosody rate="fast">ワールド</prosody>
+    </speak>"#;
+    let audio = synthesizer
+        .synthesis_ssml(ssml, style_id, &Default::default())
+        .unwrap();
+
+    assert_eq!(audio.marks.len(), 1);
+    assert_eq!(audio.marks[0].name, "world");
+    assert!(audio.marks[0].time >= std::time::Duration::from_secs(1));
+    assert_eq!(&audio.wav[0..4], b"RIFF");
+}
+
+#[test]
+fn test_project_round_trip() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let texts = ["ハローワールド", "こんにちは"];
+    let mut project = vv::Project::new(texts.iter().map(|text| {
+        let audio_query = synthesizer.create_audio_query(text, style_id).unwrap();
+        vv::ProjectAudioItem::new(text, style_id, Some(audio_query))
+    }));
+    assert!(project.to_json().is_err());
+
+    project.assign_speakers(&synthesizer.get_metas().unwrap());
+    let json = project.to_json().unwrap();
+    let loaded = vv::Project::from_json(&json).unwrap();
+    assert_eq!(loaded, project);
+
+    let wavs = synthesizer
+        .synthesis_project(&loaded, Default::default())
+        .unwrap();
+    assert_eq!(wavs.len(), texts.len());
+}
+
+#[test]
+fn test_project_editor_file() {
+    // エディタ0.21の保存形式に合わせたファイル。`song`の`tracks`はトラックIDごとのオブジェクトになっている。
+    let project = vv::Project::from_json(include_str!("resources/editor_project.vvproj")).unwrap();
+    let texts: Vec<_> = project
+        .audio_items
+        .iter()
+        .map(|item| item.text.as_str())
+        .collect();
+    assert_eq!(texts, ["こんにちは", "ア"]);
+    let item = &project.audio_items[0];
+    assert_eq!(item.engine_id, vv::DEFAULT_ENGINE_ID);
+    assert_eq!(
+        item.speaker_id.as_deref(),
+        Some("388f246b-8c41-4ac1-8e2d-5d79f3ff56d9")
+    );
+    assert_eq!(item.style_id, 3);
+    let audio_query = item.audio_query.as_ref().unwrap();
+    assert_eq!(audio_query.speed_scale, 1.1);
+    assert_eq!(audio_query.kana.as_deref(), Some("コンニチワ'"));
+    assert_eq!(audio_query.accent_phrases[0].moras.len(), 5);
+
+    // 書き出すファイルは、エディタが古い形式として移行しない0.17.0の形式にする。
+    let json: serde_json::Value = serde_json::from_str(&project.to_json().unwrap()).unwrap();
+    assert_eq!(json["appVersion"], "0.17.0");
+    assert!(json.get("audioItems").is_none());
+    assert_eq!(json["talk"]["audioKeys"].as_array().unwrap().len(), 2);
+    assert_eq!(json["song"]["tpqn"], 480);
+    let tracks = json["song"]["tracks"].as_array().unwrap();
+    assert_eq!(tracks.len(), 1);
+    assert_eq!(tracks[0]["keyRangeAdjustment"], 0);
+    assert_eq!(tracks[0]["notes"].as_array().unwrap().len(), 0);
+    assert_eq!(vv::Project::from_json(&json.to_string()).unwrap(), project);
+}
+
+#[test]
+fn test_project_legacy() {
+    let json = r#"{
+        "appVersion": "0.14.0",
+        "audioKeys": ["b", "a"],
+        "audioItems": {
+            "a": { "text": "二番目", "engineId": "engine", "styleId": 1 },
+            "b": {
+                "text": "一番目",
+                "styleId": 2,
+                "query": {
+                    "accentPhrases": [{
+                        "moras": [
+                            { "text": "ア", "vowel": "a", "vowelLength": 0.1, "pitch": 5.5 },
+                            { "text": "カ", "consonant": "k", "consonantLength": 0.05, "vowel": "a", "vowelLength": 0.1, "pitch": 5.6 }
+                        ],
+                        "accent": 1,
+                        "pauseMora": null
+                    }],
+                    "speedScale": 1.0,
+                    "pitchScale": 0.0,
+                    "intonationScale": 1.0,
+                    "volumeScale": 1.0,
+                    "prePhonemeLength": 0.1,
+                    "postPhonemeLength": 0.1,
+                    "outputSamplingRate": 24000,
+                    "outputStereo": false
+                }
+            }
+        }
+    }"#;
+
+    let project = vv::Project::from_json(json).unwrap();
+    assert_eq!(project.audio_items.len(), 2);
+    assert_eq!(project.audio_items[0].text, "一番目");
+    assert_eq!(project.audio_items[0].engine_id, vv::DEFAULT_ENGINE_ID);
+    let audio_query = project.audio_items[0].audio_query.as_ref().unwrap();
+    assert_eq!(
+        audio_query.accent_phrases[0].moras[1].consonant.as_deref(),
+        Some("k")
+    );
+    assert_eq!(project.audio_items[1].engine_id, "engine");
+    assert_eq!(project.audio_items[1].style_id, 1);
+    assert!(project.audio_items[1].audio_query.is_none());
+}
+
+fn create_kana_audio_query(kana: &str) -> vv::AudioQuery {
+    let mut accent_phrases = vv::parse_kana(kana).unwrap();
+    for (index, mora) in accent_phrases
+        .iter_mut()
+        .flat_map(|accent_phrase| &mut accent_phrase.moras)
+        .enumerate()
+    {
+        mora.pitch = vv::note_to_log_f0(60.0 + index as f32);
+        mora.vowel_length = 0.2;
+        if let Some(consonant_length) = &mut mora.consonant_length {
+            *consonant_length = 0.05;
+        }
+    }
+    for pause_mora in accent_phrases
+        .iter_mut()
+        .filter_map(|accent_phrase| accent_phrase.pause_mora.as_mut())
+    {
+        pause_mora.vowel_length = 0.3;
+    }
+
+    vv::AudioQuery {
+        accent_phrases,
+        speed_scale: 1.0,
+        pitch_scale: 0.0,
+        intonation_scale: 1.0,
+        volume_scale: 1.0,
+        pre_phoneme_length: 0.1,
+        post_phoneme_length: 0.1,
+        output_sampling_rate: 24000,
+        output_stereo: false,
+        kana: None,
+    }
+}
+
+#[test]
+fn test_midi_conversion() {
+    assert_eq!(vv::note_to_hz(69.0), 440.0);
+    assert!((vv::hz_to_note(261.63) - 60.0).abs() < 0.01);
+    assert!((vv::log_f0_to_note(vv::note_to_log_f0(64.0)) - 64.0).abs() < 1e-4);
+    assert!((vv::log_f0_to_hz(vv::hz_to_log_f0(220.0)) - 220.0).abs() < 1e-3);
+
+    let tempo_map = vv::TempoMap::new(480, vec![(960, 250_000)]);
+    assert_eq!(tempo_map.changes[0], (0, 500_000));
+    assert_eq!(tempo_map.tick_to_seconds(960), 1.0);
+    assert_eq!(tempo_map.tick_to_seconds(1440), 1.25);
+    assert_eq!(tempo_map.seconds_to_tick(0.5), 480);
+    assert_eq!(tempo_map.seconds_to_tick(1.25), 1440);
+}
+
+#[test]
+fn test_midi_round_trip() {
+    let audio_query = create_kana_audio_query("ド'レミ/ファ'、ソ'ラシ");
+    let notes = audio_query.to_midi_notes();
+    assert_eq!(notes.len(), 7);
+    assert_eq!(notes[0].key, 60);
+    assert_eq!(notes[0].lyric.as_deref(), Some("ド"));
+
+    for tempo_map in [
+        vv::TempoMap::constant(120.0, 480),
+        vv::TempoMap::new(960, vec![(0, 400_000), (1000, 700_000)]),
+    ] {
+        let smf = vv::write_midi(&notes, &tempo_map).unwrap();
+        let (read_notes, read_tempo_map) = vv::read_midi(&smf, 0).unwrap();
+        assert_eq!(read_tempo_map, tempo_map);
+        assert_eq!(read_notes.len(), notes.len());
+        for (read, note) in read_notes.iter().zip(&notes) {
+            assert_eq!(read.key, note.key);
+            assert_eq!(read.lyric, note.lyric);
+            assert!((read.start - note.start).abs() < 1e-3);
+            assert!((read.duration - note.duration).abs() < 2e-3);
+        }
+
+        let mut sung = create_kana_audio_query("ド'レミ/ファ'、ソ'ラシ");
+        for mora in sung
+            .accent_phrases
+            .iter_mut()
+            .flat_map(|accent_phrase| &mut accent_phrase.moras)
+        {
+            mora.pitch = 0.0;
+        }
+        assert_eq!(sung.apply_midi_notes(&read_notes), 7);
+        let sung_notes = sung.to_midi_notes();
+        for (sung, note) in sung_notes.iter().zip(&notes) {
+            assert_eq!(sung.key, note.key);
+            assert!((sung.start - note.start).abs() < 2e-3);
+        }
+        let pause_mora = sung.accent_phrases[1].pause_mora.as_ref().unwrap();
+        assert!((pause_mora.vowel_length - 0.3).abs() < 2e-3);
+    }
+}
+
+#[test]
+fn test_midi_pitch_and_intonation_scale() {
+    let keys = |audio_query: &vv::AudioQuery| -> Vec<u8> {
+        audio_query
+            .to_midi_notes()
+            .iter()
+            .map(|note| note.key)
+            .collect()
+    };
+    let mut audio_query = create_kana_audio_query("ド'レミ/ファ'、ソ'ラシ");
+    let pitches: Vec<f32> = audio_query
+        .accent_phrases
+        .iter()
+        .flat_map(|accent_phrase| &accent_phrase.moras)
+        .map(|mora| mora.pitch)
+        .collect();
+    let original = keys(&audio_query);
+
+    // コアと同じく、対数の音高を2^pitch_scale倍する。
+    audio_query.pitch_scale = 0.05;
+    let scaled: Vec<f32> = pitches
+        .iter()
+        .map(|pitch| pitch * 2f32.powf(0.05))
+        .collect();
+    let expected: Vec<u8> = scaled
+        .iter()
+        .map(|&pitch| vv::log_f0_to_note(pitch).round() as u8)
+        .collect();
+    assert_eq!(keys(&audio_query), expected);
+    assert!(expected
+        .iter()
+        .zip(&original)
+        .all(|(scaled, key)| scaled > key));
+
+    // 抑揚は有声のモーラの音高の平均を中心に広げる。
+    audio_query.intonation_scale = 1.5;
+    let mean = scaled.iter().sum::<f32>() / scaled.len() as f32;
+    let expected: Vec<u8> = scaled
+        .iter()
+        .map(|&pitch| vv::log_f0_to_note((pitch - mean) * 1.5 + mean).round() as u8)
+        .collect();
+    assert_eq!(keys(&audio_query), expected);
+    assert!(expected[6] - expected[0] > original[6] - original[0]);
+
+    // 書き出したノートを読み込むと、全体の設定を既定値に戻しても同じノートになる。
+    let mut sung = audio_query.clone();
+    assert_eq!(sung.apply_midi_notes(&audio_query.to_midi_notes()), 7);
+    assert_eq!(sung.pitch_scale, 0.0);
+    assert_eq!(sung.intonation_scale, 1.0);
+    assert_eq!(keys(&sung), expected);
+}
+
+#[test]
+fn test_midi_round_trip_unvoiced() {
+    // 無声化したキと促音はノートにならない。
+    let mut audio_query = create_kana_audio_query("_キ'ット/ミ'ンナ");
+    for mora in audio_query
+        .accent_phrases
+        .iter_mut()
+        .flat_map(|accent_phrase| &mut accent_phrase.moras)
+        .filter(|mora| mora.vowel == "I" || mora.vowel == "cl")
+    {
+        mora.pitch = 0.0;
+    }
+    let notes = audio_query.to_midi_notes();
+    let lyrics: Vec<_> = notes.iter().map(|note| note.lyric.as_deref()).collect();
+    assert_eq!(lyrics, [Some("ト"), Some("ミ"), Some("ン"), Some("ナ")]);
+
+    // 歌詞があってもなくても、書き出したときと同じモーラに割り当てる。
+    for with_lyric in [true, false] {
+        let notes: Vec<_> = notes
+            .iter()
+            .map(|note| vv::MidiNote {
+                lyric: note.lyric.clone().filter(|_| with_lyric),
+                ..note.clone()
+            })
+            .collect();
+        let mut sung = audio_query.clone();
+        assert_eq!(sung.apply_midi_notes(&notes), 4);
+
+        let moras: Vec<_> = sung
+            .accent_phrases
+            .iter()
+            .flat_map(|accent_phrase| &accent_phrase.moras)
+            .map(|mora| (mora.text.as_str(), mora.vowel.as_str(), mora.pitch > 0.0))
+            .collect();
+        assert_eq!(
+            moras,
+            [
+                ("キ", "I", false),
+                ("ッ", "cl", false),
+                ("ト", "o", true),
+                ("ミ", "i", true),
+                ("ン", "N", true),
+                ("ナ", "a", true),
+            ]
+        );
+        let sung_notes = sung.to_midi_notes();
+        assert_eq!(sung_notes.len(), notes.len());
+        for (sung, note) in sung_notes.iter().zip(&notes) {
+            assert_eq!(sung.key, note.key);
+            assert!((sung.start - note.start).abs() < 1e-4);
+            assert!((sung.duration - note.duration).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn test_sing_from_midi() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let notes: Vec<_> = [60, 62, 64, 65, 67, 69, 71]
+        .into_iter()
+        .enumerate()
+        .map(|(index, key)| vv::MidiNote {
+            key,
+            velocity: 100,
+            start: 0.5 * index as f64,
+            duration: 0.4,
+            lyric: None,
+        })
+        .collect();
+    let tempo_map = vv::TempoMap::constant(120.0, 480);
+    let (notes, _) = vv::read_midi(&vv::write_midi(&notes, &tempo_map).unwrap(), 0).unwrap();
+
+    let mut audio_query = synthesizer
+        .create_audio_query_from_kana("ド'レミファソラシ", style_id)
+        .unwrap();
+    assert_eq!(audio_query.apply_midi_notes(&notes), 7);
+    synthesizer
+        .synthesis(&audio_query, style_id, Default::default())
+        .unwrap();
+}
+
+#[test]
+fn test_blend_accent_phrases() {
+    let base = create_kana_audio_query("ア'イウ/エ'オ").accent_phrases;
+    let mut other = base.clone();
+    for mora in other
+        .iter_mut()
+        .flat_map(|accent_phrase| &mut accent_phrase.moras)
+    {
+        mora.pitch += 1.0;
+        mora.vowel_length *= 2.0;
+    }
+
+    let blended = vv::blend_accent_phrases(&base, &other, &vv::BlendWeight::Constant(0.3));
+    let mora = &blended[0].moras[0];
+    assert!((mora.pitch - (base[0].moras[0].pitch + 0.3)).abs() < 1e-5);
+    assert!((mora.vowel_length - 0.26).abs() < 1e-5);
+
+    let curve = vv::BlendWeight::Curve(vec![(0.0, 0.0), (1.0, 1.0)]);
+    assert_eq!(curve.at(-1.0), 0.0);
+    assert_eq!(curve.at(0.25), 0.25);
+    assert_eq!(curve.at(2.0), 1.0);
+    let blended = vv::blend_accent_phrases(&base, &other, &curve);
+    let weights: Vec<f32> = blended
+        .iter()
+        .zip(&base)
+        .flat_map(|(blended, base)| blended.moras.iter().zip(&base.moras))
+        .map(|(blended, base)| blended.pitch - base.pitch)
+        .collect();
+    assert!(weights.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(weights[0] > 0.0 && weights[weights.len() - 1] < 1.0);
+}
+
+#[test]
+fn test_synthesis_blend() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+    let other_style_id = synthesizer
+        .get_metas()
+        .unwrap()
+        .iter()
+        .flat_map(|meta| meta.styles())
+        .map(|style| style.id())
+        .find(|id| *id != style_id)
+        .unwrap();
+
+    let audio_query = synthesizer
+        .create_audio_query("今日はいい天気ですね", style_id)
+        .unwrap();
+    let blended = synthesizer
+        .blend_mora_data(
+            &audio_query.accent_phrases,
+            style_id,
+            other_style_id,
+            &vv::BlendWeight::Constant(0.0),
+        )
+        .unwrap();
+    assert_eq!(
+        blended,
+        synthesizer
+            .replace_mora_data(&audio_query.accent_phrases, style_id)
+            .unwrap()
+    );
+
+    synthesizer
+        .synthesis_blend(
+            "今日はいい天気ですね",
+            style_id,
+            other_style_id,
+            &vv::BlendWeight::Constant(0.3),
+            style_id,
+            Default::default(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_normalize_corpus() {
+    let normalizer = vv::TextNormalizer::default();
+    let corpus = [
+        // 数と助数詞
+        ("1本", "イッポン"),
+        ("2本", "ニホン"),
+        ("3本", "サンボン"),
+        ("4本", "ヨンホン"),
+        ("6本", "ロッポン"),
+        ("8本", "ハッポン"),
+        ("10本", "ジュッポン"),
+        ("100本", "ヒャッポン"),
+        ("300本", "サンビャッポン"),
+        ("600杯", "ロッピャッパイ"),
+        ("800匹", "ハッピャッピキ"),
+        ("1000本", "センボン"),
+        ("3匹", "サンビキ"),
+        ("1杯", "イッパイ"),
+        ("3分", "サンプン"),
+        ("4分", "ヨンプン"),
+        ("5分", "ゴフン"),
+        ("1個", "イッコ"),
+        ("6回", "ロッカイ"),
+        ("3階", "サンガイ"),
+        ("3軒", "サンゲン"),
+        ("1冊", "イッサツ"),
+        ("6冊", "ロクサツ"),
+        ("20歳", "ハタチ"),
+        ("21歳", "ニジュウイッサイ"),
+        ("3足", "サンゾク"),
+        ("1人", "ヒトリ"),
+        ("2人", "フタリ"),
+        ("4人", "ヨニン"),
+        ("14人", "ジュウヨニン"),
+        ("3人", "サンニン"),
+        ("1つ", "ヒトツ"),
+        ("8つ", "ヤッツ"),
+        ("3日", "ミッカ"),
+        ("14日", "ジュウヨッカ"),
+        ("20日", "ハツカ"),
+        ("25日", "ニジュウゴニチ"),
+        ("6ヶ月", "ロッカゲツ"),
+        ("10か月", "ジュッカゲツ"),
+        ("4年", "ヨネン"),
+        ("2時間", "ニジカン"),
+        ("4時間", "ヨジカン"),
+        ("1,000枚", "センマイ"),
+        ("1.5倍", "イッテンゴバイ"),
+        ("300台", "サンビャクダイ"),
+        ("600人", "ロッピャクニン"),
+        ("8000人", "ハッセンニン"),
+        ("10000人", "イチマンニン"),
+        ("10000000人", "イッセンマンニン"),
+        ("100000000人", "イチオクニン"),
+        ("1000000000000人", "イッチョウニン"),
+        ("りんごを３個ください", "りんごをサンコください"),
+        // 日付
+        ("2024年1月1日", "ニセンニジュウヨネンイチガツツイタチ"),
+        ("2024/4/14", "ニセンニジュウヨネンシガツジュウヨッカ"),
+        ("2019-07-20", "ニセンジュウキュウネンシチガツハツカ"),
+        ("9月9日", "クガツココノカ"),
+        ("令和元年", "レイワガンネン"),
+        ("令和6年", "レイワロクネン"),
+        ("平成4年", "ヘイセイヨネン"),
+        ("昭和64年", "ショウワロクジュウヨネン"),
+        // 時刻
+        ("12:00", "ジュウニジ"),
+        ("7:30", "シチジサンジュップン"),
+        ("9:05", "クジゴフン"),
+        ("14:01", "ジュウヨジイップン"),
+        ("19:15", "ジュウクジジュウゴフン"),
+        ("23:59:30", "ニジュウサンジゴジュウキュウフンサンジュウビョウ"),
+        ("25:00", "25:00"),
+        // 金額
+        ("¥1,000", "センエン"),
+        ("￥4", "ヨエン"),
+        ("500円", "ゴヒャクエン"),
+        ("$5", "ゴドル"),
+        ("$1.50", "イッテンゴゼロドル"),
+        ("€20", "ニジュウユーロ"),
+        // 割合
+        ("50%", "ゴジュウパーセント"),
+        ("99.9％", "キュウジュウキュウテンキュウパーセント"),
+        // URL・メールアドレス
+        ("詳細はhttps://example.com/a?b=c を参照", "詳細はユーアールエル を参照"),
+        ("https://example.com/a_bを見て", "ユーアールエルを見て"),
+        (
+            "連絡先はtaro.v@example.jp",
+            "連絡先はティーエーアールオードットブイアットマークイーエックスエーエムピーエルイードットジェーピー",
+        ),
+        (
+            "3人にfoo@example.comまで",
+            "サンニンにエフオーオーアットマークイーエックスエーエムピーエルイードットシーオーエムまで",
+        ),
+        // 略語
+        ("NHKを見る", "エヌエイチケーを見る"),
+        ("PDF化", "ピーディーエフ化"),
+        ("AIとVoice", "エーアイとボイス"),
+        ("ＵＳＢ", "ユーエスビー"),
+        // 絵文字
+        ("ありがとう🙏", "ありがとうオネガイ"),
+        ("いいね👍🏻", "いいねイイネ"),
+        ("❤️", "ハート"),
+        ("🦀", ""),
+        // 変換しないもの
+        ("こんにちは", "こんにちは"),
+        ("3", "3"),
+    ];
+    for (text, expected) in corpus {
+        assert_eq!(normalizer.normalize(text), expected, "{}", text);
+    }
+    assert_eq!(vv::number_reading(0), "ゼロ");
+    assert_eq!(
+        vv::number_reading(1_234_567),
+        "ヒャクニジュウサンマンヨンセンゴヒャクロクジュウナナ"
+    );
+}
+
+#[test]
+fn test_normalize_options() {
+    let text = "NHKで3本の動画を12:30に見た😊";
+
+    let mut normalizer = vv::TextNormalizer::new(vv::NormalizeOptions {
+        acronyms: false,
+        times: false,
+        ..Default::default()
+    });
+    assert_eq!(
+        normalizer.normalize(text),
+        "NHKでサンボンの動画を12:30に見たニコニコ"
+    );
+
+    normalizer.add_emoji("😊", "ウレシイ");
+    assert_eq!(
+        normalizer.normalize(text),
+        "NHKでサンボンの動画を12:30に見たウレシイ"
+    );
+
+    let normalizer = vv::TextNormalizer::new(vv::NormalizeOptions {
+        width: false,
+        counters: false,
+        dates: false,
+        times: false,
+        currency: false,
+        percentages: false,
+        urls: false,
+        emails: false,
+        acronyms: false,
+        english: false,
+        emoji: false,
+    });
+    assert_eq!(normalizer.normalize(text), text);
+}
+
+#[test]
+fn test_tts_normalized() {
+    let (_open_jtalk, synthesizer, style_id) = create_synthesizer();
+    let normalizer = vv::TextNormalizer::default();
+    let audio_query = synthesizer
+        .create_audio_query_normalized("3本のNHK番組", style_id, &normalizer)
+        .unwrap();
+    assert_eq!(
+        audio_query,
+        synthesizer
+            .create_audio_query("サンボンのエヌエイチケー番組", style_id)
+            .unwrap()
+    );
+    let wav = synthesizer
+        .tts_normalized("3本のNHK番組", style_id, &normalizer, Default::default())
+        .unwrap();
+    assert!(!wav.is_empty());
+}
+
+#[test]
+fn test_english_transliteration() {
+    let mut transliterator = vv::EnglishTransliterator::default();
+    assert!(!transliterator.is_empty());
+
+    let corpus = [
+        // 辞書
+        ("voice", "ボイス"),
+        ("Hello", "ハロー"),
+        ("games", "ゲーム"),
+        ("playing", "プレイイング"),
+        ("clubs", "クラブズ"),
+        // 綴りからの推定
+        ("cat", "カット"),
+        ("dog", "ドッグ"),
+        ("time", "タイム"),
+        ("cute", "キュート"),
+        ("bridge", "ブリッジ"),
+        ("match", "マッチ"),
+        ("think", "シンク"),
+        ("quick", "クイック"),
+        ("night", "ナイト"),
+        ("street", "ストリート"),
+        ("book", "ブック"),
+        ("system", "システム"),
+        ("music", "ミュージック"),
+        ("singer", "シンガー"),
+        ("tiger", "タイガー"),
+        ("paper", "ペーパー"),
+        ("station", "ステーション"),
+        ("rain", "レイン"),
+        ("jump", "ジャンプ"),
+        ("black", "ブラック"),
+        ("smile", "スマイル"),
+        ("x", "エックス"),
+    ];
+    for (word, expected) in corpus {
+        assert_eq!(transliterator.transliterate(word), expected, "{}", word);
+    }
+    // 語頭の`sion`
+    assert_eq!(transliterator.transliterate("Sion"), "ション");
+
+    // 読みが作れない単語はユーザー辞書の単語にしない。
+    assert_eq!(transliterator.transliterate("hh"), "");
+    assert!(transliterator.user_dict_word("hh").is_none());
+    let words = transliterator.user_dict_words("hh crab");
+    assert_eq!(words.len(), 1);
+    assert_eq!(words[0].surface, "crab");
+
+    assert_eq!(
+        transliterator.transliterate_text("Rustでcrateを作るNHK"),
+        "ラストでクレートを作るNHK"
+    );
+
+    transliterator.insert("Crate", "クレイト");
+    assert_eq!(transliterator.lookup("crate"), Some("クレイト"));
+    assert_eq!(
+        transliterator.load_tsv("# comment\nferris\tフェリス\n\ninvalid line\n"),
+        1
+    );
+    assert_eq!(transliterator.transliterate("Ferris"), "フェリス");
+    assert!(vv::EnglishTransliterator::empty().lookup("voice").is_none());
+
+    let normalizer = vv::TextNormalizer::default();
+    assert_eq!(
+        normalizer.normalize("今日のstreamはYouTubeで"),
+        "今日のストリームはユーチューブで"
+    );
+}
+
+#[test]
+fn test_english_user_dict() {
+    let (open_jtalk, synthesizer, style_id) = create_synthesizer();
+    let transliterator = vv::EnglishTransliterator::default();
+
+    let words = transliterator.user_dict_words("Ferris the crab and ferris, NHK and a crab");
+    let surfaces: Vec<_> = words.iter().map(|word| word.surface.as_str()).collect();
+    assert_eq!(surfaces, ["Ferris", "the", "crab", "and"]);
+
+    let dict = vv::UserDict::new().unwrap();
+    for word in words {
+        dict.add_word(word).unwrap();
+    }
+    open_jtalk.use_user_dict(&dict).unwrap();
+
+    let kana = synthesizer
+        .create_audio_query("Ferris", style_id)
+        .unwrap()
+        .kana
+        .unwrap();
+    assert!(kana.contains("フェ"), "{}", kana);
+}
+
+#[test]
+fn test_sanitize_mentions() {
+    let mut sanitizer = vv::ChatSanitizer::default();
+    sanitizer.add_user("123", "ずんだもん");
+    assert_eq!(
+        sanitizer.sanitize("<@123> こんにちは"),
+        "ずんだもん こんにちは"
+    );
+    assert_eq!(sanitizer.sanitize("<@!123> やあ"), "ずんだもん やあ");
+    assert_eq!(sanitizer.sanitize("<@&456> 集合"), "メンション 集合");
+    assert_eq!(sanitizer.sanitize("@taro おはよう"), "taro おはよう");
+    assert_eq!(sanitizer.sanitize("mail@example"), "mail@example");
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        mentions: vv::ReplacePolicy::Remove,
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize("<@999>おはよう"), "おはよう");
+}
+
+#[test]
+fn test_sanitize_channels() {
+    let mut sanitizer = vv::ChatSanitizer::default();
+    sanitizer.add_channel("42", "雑談");
+    assert_eq!(sanitizer.sanitize("<#42>に来て"), "雑談に来て");
+    assert_eq!(sanitizer.sanitize("<#43>に来て"), "チャンネルに来て");
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        channels: vv::ReplacePolicy::Keep,
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize("<#43>に来て"), "<#43>に来て");
+}
+
+#[test]
+fn test_sanitize_urls() {
+    let sanitizer = vv::ChatSanitizer::default();
+    assert_eq!(
+        sanitizer.sanitize("見て https://example.com/a?b=c 面白い"),
+        "見て URL省略 面白い"
+    );
+    assert_eq!(
+        sanitizer.sanitize("https://example.com/a_bを見て"),
+        "URL省略を見て"
+    );
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        urls: vv::ReplacePolicy::Replace("リンク".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize("http://example.com"), "リンク");
+}
+
+#[test]
+fn test_sanitize_code_blocks() {
+    let message = "これ見て```rust\nfn main() {}\n```どう？`x`も";
+
+    let sanitizer = vv::ChatSanitizer::default();
+    assert_eq!(sanitizer.sanitize(message), "これ見てrustのコードどう？xも");
+    assert_eq!(sanitizer.sanitize("```\nabc\n```"), "コード");
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        code_blocks: vv::CodeBlockPolicy::Skip,
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize(message), "これ見てどう？xも");
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        code_blocks: vv::CodeBlockPolicy::Keep,
+        ..Default::default()
+    });
+    assert_eq!(
+        sanitizer.sanitize(message),
+        "これ見てfn main() {}\nどう？xも"
+    );
+}
+
+#[test]
+fn test_sanitize_repeats() {
+    let sanitizer = vv::ChatSanitizer::default();
+    assert_eq!(sanitizer.sanitize("それなwwwww"), "それなわらわら");
+    assert_eq!(sanitizer.sanitize("草w"), "草わら");
+    assert_eq!(sanitizer.sanitize("ｗｗｗ"), "わらわら");
+    assert_eq!(sanitizer.sanitize("www wow"), "わらわら wow");
+    assert_eq!(sanitizer.sanitize("すごーーーーい"), "すごーい");
+    assert_eq!(sanitizer.sanitize("!!!!!!!!"), "!!!");
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        collapse_repeats: false,
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize("wwwww"), "wwwww");
+}
+
+#[test]
+fn test_sanitize_emoji() {
+    let mut sanitizer = vv::ChatSanitizer::default();
+    sanitizer.add_custom_emoji("zunda", "ずんだ");
+    assert_eq!(sanitizer.sanitize("<:zunda:111>おいしい"), "ずんだおいしい");
+    assert_eq!(sanitizer.sanitize("<a:party:222>"), "party");
+    assert_eq!(sanitizer.sanitize("疲れた(´・ω・`)"), "疲れたショボーン");
+    assert_eq!(sanitizer.sanitize("負けたorz"), "負けたガックリ");
+
+    sanitizer.add_kaomoji("(^^)", "ニッコリ");
+    assert_eq!(sanitizer.sanitize("よろしく(^^)"), "よろしくニッコリ");
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        custom_emoji: vv::ReplacePolicy::Remove,
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize("<:zunda:111>おいしい"), "おいしい");
+}
+
+#[test]
+fn test_sanitize_max_length() {
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        max_length: Some(5),
+        ..Default::default()
+    });
+    assert_eq!(sanitizer.sanitize("あいうえお"), "あいうえお");
+    assert_eq!(
+        sanitizer.sanitize("あいうえおかきくけこ"),
+        "あいうえお以下略"
+    );
+
+    let sanitizer = vv::ChatSanitizer::new(vv::SanitizeOptions {
+        max_length: None,
+        ..Default::default()
+    });
+    let long = "あいうえお".repeat(60);
+    assert_eq!(sanitizer.sanitize(&long), long);
+}
+
+#[test]
+fn test_replace_dict() {
+    let mut dict = vv::ReplaceDict::new();
+    dict.add_rule(vv::ReplaceRule::literal("ｗ", "わら"))
+        .unwrap();
+    dict.add_rule(vv::ReplaceRule::regex(r"(\w+)氏", "${1}さん"))
+        .unwrap();
+    dict.add_rule(vv::ReplaceRule::literal("ずんだ", "ずんだもん").with_priority(10))
+        .unwrap();
+    dict.add_rule(vv::ReplaceRule::literal("もんもん", "もん").with_priority(10))
+        .unwrap();
+    assert!(dict.add_rule(vv::ReplaceRule::regex("(", "")).is_err());
+
+    let patterns: Vec<_> = dict.rules().map(|rule| rule.pattern.as_str()).collect();
+    assert_eq!(patterns, ["ずんだ", "もんもん", "ｗ", r"(\w+)氏"]);
+    assert_eq!(dict.apply("ずんだ氏ｗ"), "ずんだもんさんわら");
+    assert_eq!(dict.apply("ずんだもん"), "ずんだもん");
+
+    assert_eq!(
+        vv::ReplaceDict::from_json(&dict.to_json().unwrap())
+            .unwrap()
+            .rules()
+            .collect::<Vec<_>>(),
+        dict.rules().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vv::ReplaceDict::from_toml(&dict.to_toml().unwrap())
+            .unwrap()
+            .rules()
+            .collect::<Vec<_>>(),
+        dict.rules().collect::<Vec<_>>()
+    );
+
+    let text =
+        vv::ReplaceDict::from_text("# 棒読みちゃん\nｗ\tわら\r\n\nおは\tおはよう\n").unwrap();
+    assert_eq!(text.len(), 2);
+    assert_eq!(text.apply("おはｗ"), "おはようわら");
+    assert_eq!(text.to_text(), "ｗ\tわら\nおは\tおはよう\n");
+    assert!(matches!(
+        vv::ReplaceDict::from_text("a\tb\nbroken"),
+        Err(vv::ReplaceDictError::InvalidLine { line: 2 })
+    ));
+
+    let mut imported = vv::ReplaceDict::new();
+    imported.import(&text);
+    imported.import(&dict);
+    assert_eq!(imported.len(), 6);
+    assert_eq!(imported.rules().next().unwrap().pattern, "ずんだ");
+    assert_eq!(imported.remove_rule(0).pattern, "ずんだ");
+    imported.clear();
+    assert!(imported.is_empty());
+}
+
+#[test]
+fn test_replace_dict_reload() {
+    let path = std::env::temp_dir().join(format!(
+        "voicevox_core_rs_replace_dict_{}.json",
+        std::process::id()
+    ));
+    let mut dict = vv::ReplaceDict::new();
+    dict.add_rule(vv::ReplaceRule::literal("ｗ", "わら"))
+        .unwrap();
+    dict.save(&path).unwrap();
+
+    let mut loaded = vv::ReplaceDict::load(&path).unwrap();
+    assert_eq!(loaded.apply("ｗ"), "わら");
+    assert!(!loaded.reload_if_changed().unwrap());
+
+    let touch = |path: &std::path::Path, seconds: u64| {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(
+                std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds),
+            )
+            .unwrap();
+    };
+
+    dict.add_rule(vv::ReplaceRule::literal("草", "くさ"))
+        .unwrap();
+    dict.save(&path).unwrap();
+    touch(&path, 1_000_000);
+    assert!(loaded.reload_if_changed().unwrap());
+    assert_eq!(loaded.apply("草ｗ"), "くさわら");
+
+    // 壊れたファイルでは今のルールを残す。
+    std::fs::write(&path, "{").unwrap();
+    touch(&path, 2_000_000);
+    assert!(loaded.reload_if_changed().is_err());
+    assert_eq!(loaded.apply("草ｗ"), "くさわら");
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        vv::ReplaceDict::load("dict.yaml"),
+        Err(vv::ReplaceDictError::Io(_))
+    ));
+    assert!(matches!(
+        dict.save("dict.yaml"),
+        Err(vv::ReplaceDictError::UnsupportedExtension(_))
+    ));
+}
+
+#[test]
+fn test_tts_with_replace_dict() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+    let mut dict = vv::ReplaceDict::new();
+    dict.add_rule(vv::ReplaceRule::literal("ｗ", "わら"))
+        .unwrap();
+
+    assert_eq!(
+        synthesizer
+            .create_audio_query_with_replace_dict("ｗ", style_id, &dict)
+            .unwrap(),
+        synthesizer.create_audio_query("わら", style_id).unwrap()
+    );
+    synthesizer
+        .tts_with_replace_dict("ｗ", style_id, &dict, Default::default())
+        .unwrap();
+}
+
+fn number_to_kana(mut n: usize) -> String {
+    const KANA: [&str; 10] = ["ア", "イ", "ウ", "エ", "オ", "カ", "キ", "ク", "ケ", "コ"];
+    let mut kana = vec![];
+    loop {
+        kana.push(KANA[n % 10]);
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    kana.reverse();
+    kana.concat()
+}
+
+#[test]
+fn test_user_dict_query() {
+    let dict = vv::UserDict::new().unwrap();
+    assert_eq!(dict.len().unwrap(), 0);
+    assert!(dict.is_empty().unwrap());
+
+    let count = 3000;
+    let uuids: Vec<_> = (0..count)
+        .map(|i| {
+            dict.add_word(vv::UserDictWord::new(
+                &format!("word{}", i),
+                &number_to_kana(i % 1000),
+            ))
+            .unwrap()
+        })
+        .collect();
+    assert_eq!(dict.len().unwrap(), count);
+    assert!(!dict.is_empty().unwrap());
+
+    for (i, uuid) in uuids.iter().enumerate().step_by(97) {
+        let word = dict.get(uuid).unwrap().unwrap();
+        // 表記は全角で保存される。
+        let surface: String = format!("word{}", i)
+            .chars()
+            .map(|c| char::from_u32(c as u32 + 0xfee0).unwrap())
+            .collect();
+        assert_eq!(word.surface, surface);
+        assert_eq!(word.pronunciation, number_to_kana(i % 1000));
+    }
+    assert!(dict.get(&vv::Uuid::nil()).unwrap().is_none());
+
+    let found = dict.find_by_surface("word1234").unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0, uuids[1234]);
+    assert_eq!(dict.find_by_pronunciation("イウエ").unwrap().len(), 3);
+    assert_eq!(dict.find_by_pronunciation("いうえ").unwrap().len(), 3);
+    assert!(dict.find_by_surface("missing").unwrap().is_empty());
+
+    let iter = dict.iter().unwrap();
+    assert_eq!(iter.len(), count);
+    let iterated: Vec<_> = iter.map(|(uuid, _)| uuid).collect();
+    assert_eq!(iterated, uuids);
+
+    // 変更は次の呼び出しから反映される。
+    dict.remove_word(&uuids[0]).unwrap();
+    assert_eq!(dict.len().unwrap(), count - 1);
+    assert!(dict.get(&uuids[0]).unwrap().is_none());
+    dict.update_word(uuids[1], vv::UserDictWord::new("updated", "アップデート"))
+        .unwrap();
+    assert_eq!(dict.find_by_surface("updated").unwrap()[0].0, uuids[1]);
+    assert_eq!(dict.to_index_map().unwrap().len(), count - 1);
+    assert_eq!(
+        serde_json::to_value(&dict)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .len(),
+        count - 1
+    );
+}
+
+#[test]
+fn test_user_dict_csv() {
+    let dict = vv::UserDict::new().unwrap();
+    let csv = "surface,pronunciation,accent_type,word_type,priority\n\
+               ずんだもん,ズンダモン,1,PROPER_NOUN,8\n\
+               \"東北,きりたん\",とうほくきりたん,,固有名詞,\n\
+               壊れた,コワレタ,one,COMMON_NOUN,5\n\
+               謎,ナゾ,1,UNKNOWN,5\n\
+               足りない\n";
+    let report = dict.import_text(csv, vv::UserDictFormat::Csv);
+    assert_eq!(report.added.len(), 2);
+    let lines: Vec<_> = report.errors.iter().map(|error| error.line).collect();
+    assert_eq!(lines, [4, 5, 6]);
+    assert!(matches!(
+        report.errors[0].kind,
+        vv::UserDictLineErrorKind::InvalidNumber(_)
+    ));
+    assert!(matches!(
+        report.errors[1].kind,
+        vv::UserDictLineErrorKind::UnknownPartOfSpeech(_)
+    ));
+    assert!(matches!(
+        report.errors[2].kind,
+        vv::UserDictLineErrorKind::MissingColumn
+    ));
+
+    let zundamon = dict.get(&report.added[0]).unwrap().unwrap();
+    assert_eq!(zundamon.accent_type, 1);
+    assert_eq!(zundamon.priority, 8);
+    let kiritan = dict.get(&report.added[1]).unwrap().unwrap();
+    assert_eq!(kiritan.pronunciation, "トウホクキリタン");
+    assert_eq!(kiritan.word_type, vv::UserDictWordType::ProperNoun);
+
+    let exported = dict.export_text(vv::UserDictFormat::Csv).unwrap();
+    let round_trip = vv::UserDict::new().unwrap();
+    let report = round_trip.import_text(&exported, vv::UserDictFormat::Csv);
+    assert!(report.errors.is_empty());
+    let words: Vec<_> = round_trip.iter().unwrap().map(|(_, word)| word).collect();
+    let expected: Vec<_> = dict.iter().unwrap().map(|(_, word)| word).collect();
+    assert_eq!(
+        serde_json::to_value(words).unwrap(),
+        serde_json::to_value(expected).unwrap()
+    );
+}
+
+#[test]
+fn test_user_dict_ime() {
+    assert_eq!(
+        vv::UserDictWordType::from_part_of_speech("人名"),
+        Some(vv::UserDictWordType::ProperNoun)
+    );
+    assert_eq!(
+        vv::UserDictWordType::from_part_of_speech("動詞カ行五段"),
+        Some(vv::UserDictWordType::Verb)
+    );
+    assert_eq!(
+        vv::UserDictWordType::from_part_of_speech("接尾人名"),
+        Some(vv::UserDictWordType::Suffix)
+    );
+    assert_eq!(
+        vv::UserDictWordType::from_part_of_speech("さ変名詞"),
+        Some(vv::UserDictWordType::CommonNoun)
+    );
+    assert_eq!(vv::UserDictWordType::from_part_of_speech("感動詞?"), None);
+
+    let dict = vv::UserDict::new().unwrap();
+    let google =
+        "# Google IME\nずんだもん\tずんだもん\t人名\tコメント\nはしる\t走る\t動詞ラ行五段\n\
+                  おかしい\t可笑しい\t形容詞\nくん\t君\t接尾人名\nなぞ\t謎\t不明\nよみだけ\n";
+    let report = dict.import_text(google, vv::UserDictFormat::GoogleIme);
+    assert_eq!(report.added.len(), 4);
+    let lines: Vec<_> = report.errors.iter().map(|error| error.line).collect();
+    assert_eq!(lines, [6, 7]);
+    let word = dict.get(&report.added[1]).unwrap().unwrap();
+    assert_eq!(word.pronunciation, "ハシル");
+    assert_eq!(word.word_type, vv::UserDictWordType::Verb);
+
+    let path = std::env::temp_dir().join(format!(
+        "voicevox_core_rs_ms_ime_{}.txt",
+        std::process::id()
+    ));
+    dict.export_file(&path, vv::UserDictFormat::MsIme).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(&bytes[..2], [0xff, 0xfe]);
+
+    let imported = vv::UserDict::new().unwrap();
+    let report = imported
+        .import_file(&path, vv::UserDictFormat::MsIme)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(report.added.len(), 4);
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        imported.find_by_pronunciation("ずんだもん").unwrap()[0]
+            .1
+            .word_type,
+        vv::UserDictWordType::ProperNoun
+    );
+    assert_eq!(
+        imported
+            .export_text(vv::UserDictFormat::GoogleIme)
+            .unwrap()
+            .lines()
+            .count(),
+        4
+    );
+}
+
+#[test]
+fn test_engine_user_dict() {
+    let json = r#"{
+        "a89596ad-caa8-4f4e-8eb3-3d2261c798fd": {
+            "surface": "ｔｅｓｔ１",
+            "cost": 8609,
+            "context_id": 1348,
+            "part_of_speech": "名詞",
+            "part_of_speech_detail_1": "固有名詞",
+            "part_of_speech_detail_2": "一般",
+            "part_of_speech_detail_3": "*",
+            "inflectional_type": "*",
+            "inflectional_form": "*",
+            "stem": "*",
+            "yomi": "テストイチ",
+            "pronunciation": "テストイチ",
+            "accent_type": 1,
+            "accent_associative_rule": "*"
+        },
+        "c6ec8b4d-0e3a-4b2a-9c0d-3e0e6b1c6a51": {
+            "surface": "ｔｅｓｔ２",
+            "priority": 8,
+            "context_id": 642,
+            "part_of_speech": "動詞",
+            "part_of_speech_detail_1": "自立",
+            "part_of_speech_detail_2": "*",
+            "part_of_speech_detail_3": "*",
+            "inflectional_type": "五段・ラ行",
+            "inflectional_form": "基本形",
+            "stem": "*",
+            "yomi": "テストスル",
+            "pronunciation": "テストスル",
+            "accent_type": 2,
+            "mora_count": 5,
+            "accent_associative_rule": "*"
+        }
+    }"#;
+    let engine_dict = vv::EngineUserDict::from_json(json).unwrap();
+    assert_eq!(
+        vv::EngineUserDict::from_json(&engine_dict.to_json().unwrap()).unwrap(),
+        engine_dict
+    );
+
+    let words: Vec<_> = engine_dict.words.values().collect();
+    assert_eq!(words[0].word_type(), vv::UserDictWordType::ProperNoun);
+    assert_eq!(words[0].priority(), 5);
+    assert_eq!(words[1].word_type(), vv::UserDictWordType::Verb);
+    assert_eq!(words[1].priority(), 8);
+
+    let user_dict = engine_dict.to_user_dict().unwrap();
+    let uuids: Vec<_> = user_dict.iter().unwrap().map(|(uuid, _)| uuid).collect();
+    assert_eq!(uuids, engine_dict.words.keys().copied().collect::<Vec<_>>());
+    let verb = user_dict.get(&uuids[1]).unwrap().unwrap();
+    assert_eq!(verb.surface, "ｔｅｓｔ２");
+    assert_eq!(verb.accent_type, 2);
+
+    // 変更しなければ元のまま戻る。
+    let mut round_trip = engine_dict.clone();
+    round_trip.update_from(&user_dict).unwrap();
+    assert_eq!(round_trip, engine_dict);
+
+    // 変更した項目だけが書き換わる。
+    user_dict
+        .update_word(
+            uuids[0],
+            vv::UserDictWord {
+                priority: 10,
+                pronunciation: "テストワン".to_string(),
+                ..user_dict.get(&uuids[0]).unwrap().unwrap()
+            },
+        )
+        .unwrap();
+    let added = user_dict
+        .add_word(vv::UserDictWord::new("追加", "ツイカ"))
+        .unwrap();
+    user_dict.remove_word(&uuids[1]).unwrap();
+    round_trip.update_from(&user_dict).unwrap();
+    assert_eq!(round_trip.words.len(), 2);
+    let updated = &round_trip.words[&uuids[0]];
+    assert_eq!(updated.cost, Some(-988));
+    assert_eq!(updated.priority, None);
+    assert_eq!(updated.yomi, "テストワン");
+    assert_eq!(updated.context_id, 1348);
+    let added = &round_trip.words[&added];
+    assert_eq!(added.priority, Some(5));
+    assert_eq!(added.mora_count, Some(3));
+
+    let from_user_dict = vv::EngineUserDict::from_user_dict(&user_dict).unwrap();
+    assert_eq!(from_user_dict.words.len(), 2);
+    assert!(from_user_dict
+        .words
+        .values()
+        .all(|word| word.cost.is_none()));
+}
+
+#[test]
+fn test_user_dict_word_builder() {
+    assert_eq!(vv::count_moras("キャット"), 3);
+    assert_eq!(vv::count_moras("ラーメン"), 4);
+    assert_eq!(vv::count_moras("ヴァイオリン"), 5);
+
+    let word = vv::UserDictWord::builder("ちゅーりっぷ", "ちゅーりっぷ")
+        .accent_type(1)
+        .word_type(vv::UserDictWordType::CommonNoun)
+        .priority(10)
+        .build()
+        .unwrap();
+    assert_eq!(word.pronunciation, "チューリップ");
+    assert_eq!(word.accent_type, 1);
+    assert_eq!(word.priority, 10);
+    assert!(matches!(word.word_type, vv::UserDictWordType::CommonNoun));
+
+    let error = |surface: &str, pronunciation: &str, accent_type: usize, priority: u32| {
+        vv::UserDictWord::builder(surface, pronunciation)
+            .accent_type(accent_type)
+            .priority(priority)
+            .build()
+            .unwrap_err()
+    };
+    assert_eq!(
+        error(" ", "テスト", 0, 5),
+        vv::UserDictWordError::EmptySurface
+    );
+    assert_eq!(
+        error("テスト", "", 0, 5),
+        vv::UserDictWordError::EmptyPronunciation
+    );
+    assert_eq!(
+        error("テスト", "テスto", 0, 5),
+        vv::UserDictWordError::NotKatakana {
+            character: 't',
+            position: 3,
+        }
+    );
+    assert_eq!(
+        error("テスト", "ーテスト", 0, 5),
+        vv::UserDictWordError::InvalidFirstCharacter('ー')
+    );
+    assert_eq!(
+        error("テスト", "テンャ", 0, 5),
+        vv::UserDictWordError::MisplacedSmallKana {
+            character: 'ャ',
+            position: 3,
+        }
+    );
+    assert_eq!(
+        error("テスト", "テスト", 4, 5),
+        vv::UserDictWordError::AccentTypeOutOfRange {
+            accent_type: 4,
+            mora_count: 3,
+        }
+    );
+    assert_eq!(
+        error("テスト", "テスト", 0, 11),
+        vv::UserDictWordError::PriorityOutOfRange(11)
+    );
+    assert_eq!(
+        vv::UserDictWordError::AccentTypeOutOfRange {
+            accent_type: 4,
+            mora_count: 3,
+        }
+        .to_string(),
+        "アクセント型4がモーラ数3を超えている"
+    );
+}
+
+#[test]
+fn test_user_dict_serde() {
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct Config {
+        name: String,
+        user_dict: vv::UserDict,
+    }
+
+    let dict: vv::UserDict = [
+        vv::UserDictWord::new("東北", "トウホク"),
+        vv::UserDictWord::new("ずんだ", "ズンダ"),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(dict.len().unwrap(), 2);
+
+    let config = Config {
+        name: "test".to_string(),
+        user_dict: dict,
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    let round_trip: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip.name, "test");
+    assert_eq!(
+        round_trip
+            .user_dict
+            .to_index_map()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>(),
+        config
+            .user_dict
+            .to_index_map()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(serde_json::to_string(&round_trip).unwrap(), json);
+
+    let copied: vv::UserDict = config.user_dict.iter().unwrap().collect();
+    assert_eq!(
+        serde_json::to_string(&copied).unwrap(),
+        serde_json::to_string(&config.user_dict).unwrap()
+    );
+
+    assert!(serde_json::from_str::<vv::UserDict>(r#"{"not-a-uuid": {}}"#).is_err());
+}
+
+#[test]
+fn test_user_dict_watcher() {
+    let open_jtalk = vv::OpenJtalkRc::new(test_resources::get_dict_path()).unwrap();
+    let path = std::env::temp_dir().join(format!(
+        "voicevox_core_rs_user_dict_watcher_{}.json",
+        std::process::id()
+    ));
+    let touch = |path: &std::path::Path, seconds: u64| {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(
+                std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds),
+            )
+            .unwrap();
+    };
+    let dict = vv::UserDict::new().unwrap();
+    dict.add_word(vv::UserDictWord::new("東北", "トウホク"))
+        .unwrap();
+    dict.save(&path).unwrap();
+
+    let reloads = std::rc::Rc::new(std::cell::Cell::new(0));
+    let mut watcher = vv::UserDictWatcher::new(&path, &open_jtalk)
+        .unwrap()
+        .with_debounce(std::time::Duration::ZERO)
+        .on_reload({
+            let reloads = reloads.clone();
+            move |_| reloads.set(reloads.get() + 1)
+        });
+    assert_eq!(watcher.user_dict().len().unwrap(), 1);
+    assert!(watcher.poll(&open_jtalk).is_none());
+
+    dict.add_word(vv::UserDictWord::new("ずんだ", "ズンダ"))
+        .unwrap();
+    dict.save(&path).unwrap();
+    touch(&path, 1_000_000);
+    assert!(matches!(
+        watcher.poll(&open_jtalk),
+        Some(vv::UserDictReloadEvent::Reloaded { words: 2 })
+    ));
+    assert!(watcher.poll(&open_jtalk).is_none());
+    assert_eq!(reloads.get(), 1);
+
+    // 壊れたファイルでは前の辞書を残し、次に更新されるまで読み込まない。
+    std::fs::write(&path, "{").unwrap();
+    touch(&path, 2_000_000);
+    assert!(matches!(
+        watcher.poll(&open_jtalk),
+        Some(vv::UserDictReloadEvent::Failed(_))
+    ));
+    assert!(watcher.poll(&open_jtalk).is_none());
+    assert_eq!(watcher.user_dict().len().unwrap(), 2);
+    assert_eq!(reloads.get(), 2);
+
+    // 変更が落ち着くまでは読み込まず、落ち着いてから待つ時間が過ぎたら読み込む。
+    let debounce = std::time::Duration::from_millis(50);
+    let mut watcher = watcher.with_debounce(debounce);
+    dict.save(&path).unwrap();
+    touch(&path, 3_000_000);
+    assert!(watcher.poll(&open_jtalk).is_none());
+    std::thread::sleep(debounce * 2);
+    // 待っている間に変更されたら、そこから待ちなおす。
+    touch(&path, 4_000_000);
+    assert!(watcher.poll(&open_jtalk).is_none());
+    assert!(watcher.poll(&open_jtalk).is_none());
+    assert_eq!(reloads.get(), 2);
+    std::thread::sleep(debounce * 2);
+    assert!(matches!(
+        watcher.poll(&open_jtalk),
+        Some(vv::UserDictReloadEvent::Reloaded { words: 2 })
+    ));
+    assert!(watcher.poll(&open_jtalk).is_none());
+    assert_eq!(reloads.get(), 3);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_user_dict_merge() {
+    let word = |surface: &str, pronunciation: &str, priority: u32| vv::UserDictWord {
+        priority,
+        ..vv::UserDictWord::new(surface, pronunciation)
+    };
+    let base = || -> vv::UserDict {
+        [word("東北", "トウホク", 5), word("ずんだ", "ズンダ", 5)]
+            .into_iter()
+            .collect()
+    };
+    let incoming: vv::UserDict = [
+        word("東北", "トーホク", 8),
+        word("ずんだ", "ズンダ", 5),
+        word("もち", "モチ", 5),
+    ]
+    .into_iter()
+    .collect();
+
+    let dict = base();
+    let report = dict.merge(&incoming, Default::default()).unwrap();
+    assert_eq!(report.added.len(), 1);
+    assert_eq!(report.kept.len(), 1);
+    assert_eq!(report.kept[0].incoming.pronunciation, "トーホク");
+    assert!(report.updated.is_empty());
+    assert_eq!(report.identical, 1);
+    assert_eq!(dict.len().unwrap(), 3);
+    assert_eq!(
+        dict.find_by_surface("東北").unwrap()[0].1.pronunciation,
+        "トウホク"
+    );
+
+    for (policy, pronunciation) in [
+        (vv::UserDictMergePolicy::TakeIncoming, "トーホク"),
+        (vv::UserDictMergePolicy::HigherPriority, "トーホク"),
+        (
+            vv::UserDictMergePolicy::Custom(Box::new(|existing, incoming| vv::UserDictWord {
+                priority: existing.priority.max(incoming.priority),
+                ..existing.clone()
+            })),
+            "トウホク",
+        ),
+    ] {
+        let dict = base();
+        let report = dict
+            .merge(
+                &incoming,
+                vv::UserDictMergeOptions {
+                    policy,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(report.updated.len(), 1);
+        let (uuid, updated) = dict.find_by_surface("東北").unwrap().remove(0);
+        assert_eq!(report.updated[0].uuid, uuid);
+        assert_eq!(updated.pronunciation, pronunciation);
+        assert_eq!(updated.priority, 8);
+    }
+
+    // 単語の種類が違えば衝突しない。
+    let dict = base();
+    let common_noun: vv::UserDict = [vv::UserDictWord {
+        word_type: vv::UserDictWordType::CommonNoun,
+        ..word("東北", "トーホク", 5)
+    }]
+    .into_iter()
+    .collect();
+    let report = dict
+        .merge(
+            &common_noun,
+            vv::UserDictMergeOptions {
+                by_word_type: true,
+                policy: vv::UserDictMergePolicy::TakeIncoming,
+            },
+        )
+        .unwrap();
+    assert_eq!(report.added.len(), 1);
+    assert_eq!(dict.find_by_surface("東北").unwrap().len(), 2);
+}
+
+#[test]
+fn test_suggest_accent_type() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let suggestion = synthesizer
+        .suggest_accent_type("東北", "とーほく", style_id)
+        .unwrap();
+    assert_eq!(suggestion.pronunciation, "トーホク");
+    assert_eq!(suggestion.candidates.len(), 5);
+    assert_eq!(suggestion.candidates[0].kana, "トオホクガ'");
+    assert_eq!(suggestion.candidates[1].kana, "ト'オホクガ");
+    assert_eq!(suggestion.candidates[4].kana, "トオホク'ガ");
+    for (accent_type, candidate) in suggestion.candidates.iter().enumerate() {
+        assert_eq!(candidate.accent_type, accent_type);
+        assert_eq!(candidate.accent_phrases.len(), 1);
+        assert!(candidate.wav.starts_with(b"RIFF"));
+    }
+    if let Some(predicted) = suggestion.predicted {
+        assert_eq!(suggestion.suggested, predicted);
+    }
+    assert_eq!(
+        suggestion.suggested_candidate().accent_type,
+        suggestion.suggested
+    );
+
+    assert!(matches!(
+        synthesizer.suggest_accent_type("東北", "tohoku", style_id),
+        Err(vv::AccentSuggestionError::Word(
+            vv::UserDictWordError::NotKatakana { .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_scan_corpus() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let corpus = "zundamonがかわいい\nzundamonとAPIの話\nきょうもzundamon";
+    let findings = synthesizer
+        .scan_corpus(corpus.lines(), style_id, &Default::default())
+        .unwrap();
+    let finding = findings
+        .iter()
+        .find(|finding| finding.surface == "zundamon")
+        .unwrap();
+    assert_eq!(finding.count, 3);
+    assert!(finding.kinds.contains(&vv::CorpusFindingKind::SpelledOut));
+    assert_eq!(
+        finding.candidates[0].pronunciation,
+        vv::EnglishTransliterator::default().transliterate("zundamon")
+    );
+    assert!(!findings.iter().any(|finding| finding.surface == "API"
+        && finding.kinds.contains(&vv::CorpusFindingKind::SpelledOut)));
+    assert!(findings
+        .windows(2)
+        .all(|pair| pair[0].count >= pair[1].count));
+
+    // 辞書に無い熟語は1文字ずつの読みになる。その読みを候補にはしない。
+    let kanji_corpus = "猫机が欲しい\n猫机を買った";
+    let findings = synthesizer
+        .scan_corpus(kanji_corpus.lines(), style_id, &Default::default())
+        .unwrap();
+    let finding = findings
+        .iter()
+        .find(|finding| finding.surface == "猫机")
+        .unwrap();
+    assert_eq!(finding.count, 2);
+    assert!(finding
+        .kinds
+        .contains(&vv::CorpusFindingKind::PerCharacterReading));
+    assert!(!finding
+        .candidates
+        .iter()
+        .any(|word| word.pronunciation == finding.reading));
+    if !finding
+        .kinds
+        .contains(&vv::CorpusFindingKind::VaryingReading)
+    {
+        assert!(finding.candidates.is_empty());
+    }
+
+    let findings = synthesizer
+        .scan_corpus(
+            corpus.lines(),
+            style_id,
+            &vv::CorpusScanOptions {
+                min_count: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn test_pronunciation_suite() {
+    let yaml = vv::PronunciationSuite::from_yaml(
+        "cases:\n  - text: こんにちは\n    kana: コンニチワ'\n  - text: 東北\n    kana: ト'オホク\n",
+    )
+    .unwrap();
+    let toml = vv::PronunciationSuite::from_toml(
+        "[[cases]]\ntext = \"こんにちは\"\nkana = \"コンニチワ'\"\n\n[[cases]]\ntext = \"東北\"\nkana = \"ト'オホク\"\n",
+    )
+    .unwrap();
+    assert_eq!(yaml, toml);
+    assert_eq!(yaml.cases.len(), 2);
+    assert!(matches!(
+        vv::PronunciationSuite::load("suite.json"),
+        Err(vv::PronunciationSuiteError::UnsupportedExtension(_))
+    ));
+
+    let report = vv::PronunciationReport {
+        results: vec![
+            vv::PronunciationResult {
+                case: yaml.cases[0].clone(),
+                actual: "コンニチワ'".to_string(),
+            },
+            vv::PronunciationResult {
+                case: vv::PronunciationCase {
+                    text: "東北のずんだ".to_string(),
+                    kana: "ト'オホクノ/ズンダ".to_string(),
+                },
+                actual: "ト'オホクノ/ズ'ンダ".to_string(),
+            },
+        ],
+    };
+    assert!(!report.is_success());
+    assert_eq!(report.failures().count(), 1);
+    assert_eq!(
+        report.to_string(),
+        "東北のずんだ\n- ト'オホクノ/ズンダ\n+ ト'オホクノ/ズ'ンダ\n  2番目のアクセント句：ズンダ → ズ'ンダ\n\n1/2件一致"
+    );
+}
+
+#[test]
+fn test_pronunciation_suite_run() {
+    let (_, synthesizer, style_id) = create_synthesizer();
+
+    let text = "ハローワールド";
+    let kana = synthesizer
+        .create_audio_query(text, style_id)
+        .unwrap()
+        .kana
+        .unwrap();
+    let suite = vv::PronunciationSuite {
+        cases: vec![
+            vv::PronunciationCase {
+                text: text.to_string(),
+                kana: kana.clone(),
+            },
+            vv::PronunciationCase {
+                text: text.to_string(),
+                kana: format!("{kana}/ア'"),
+            },
+        ],
+    };
+    let report = suite.run(&synthesizer, style_id).unwrap();
+    assert!(report.results[0].passed());
+    assert!(!report.results[1].passed());
+    assert!(!report.is_success());
+
+    // ドキュメントの例のカナは、実際に生成されるカナと一致する。
+    let suite =
+        vv::PronunciationSuite::from_yaml("cases:\n  - text: こんにちは\n    kana: コンニチワ'\n")
+            .unwrap();
+    assert!(suite.run(&synthesizer, style_id).unwrap().is_success());
+}
+
+#[test]
+fn test_tenant_isolation() {
+    let voice_model = vv::VoiceModel::from_path(test_resources::get_vvm_path()).unwrap();
+    let style_id = voice_model.metas()[0].styles()[0].id();
+    // コンテキストを1つに制限して、テナント間でコンテキストを使いまわさせる。
+    let mut manager = vv::TenantManager::new(
+        test_resources::get_dict_path(),
+        vec![voice_model],
+        vv::TenantOptions {
+            max_contexts: 1,
+            max_idle_contexts: 1,
+            ..Default::default()
+        },
+    );
+
+    let dummy_word = "this_is_a_very_long_phrase_that_hopefully_is_not_in_any_dictionary";
+    let dict = vv::UserDict::new().unwrap();
+    dict.add_word(vv::UserDictWord::new(dummy_word, "アイウエオ"))
+        .unwrap();
+    manager.set_user_dict("guild_a", dict).unwrap();
+
+    let kana = |manager: &mut vv::TenantManager<&str>, tenant| {
+        manager
+            .create_audio_query(&tenant, dummy_word, style_id)
+            .unwrap()
+            .kana
+            .unwrap()
+    };
+    let before = kana(&mut manager, "guild_b");
+    let with_dict = kana(&mut manager, "guild_a");
+    assert_ne!(before, with_dict);
+    assert_eq!(manager.active_tenants().collect::<Vec<_>>(), [&"guild_a"]);
+    assert_eq!(kana(&mut manager, "guild_b"), before);
+    assert_eq!(kana(&mut manager, "guild_a"), with_dict);
+
+    assert!(manager.evict_idle().is_empty());
+    assert!(manager.remove_tenant(&"guild_b").is_none());
+    assert!(manager.remove_tenant(&"guild_a").is_some());
+    assert_eq!(kana(&mut manager, "guild_a"), before);
+}
+
+#[test]
+fn test_tenant_eviction() {
+    let voice_model = vv::VoiceModel::from_path(test_resources::get_vvm_path()).unwrap();
+    let style_id = voice_model.metas()[0].styles()[0].id();
+    let mut manager = vv::TenantManager::new(
+        test_resources::get_dict_path(),
+        vec![voice_model],
+        vv::TenantOptions {
+            max_contexts: 2,
+            idle_timeout: std::time::Duration::ZERO,
+            ..Default::default()
+        },
+    );
+
+    let dummy_word = "this_is_a_very_long_phrase_that_hopefully_is_not_in_any_dictionary";
+    let dict = vv::UserDict::new().unwrap();
+    dict.add_word(vv::UserDictWord::new(dummy_word, "アイウエオ"))
+        .unwrap();
+    manager.set_user_dict(1, dict).unwrap();
+    let with_dict = manager
+        .create_audio_query(&1, dummy_word, style_id)
+        .unwrap()
+        .kana;
+    manager
+        .create_audio_query(&2, dummy_word, style_id)
+        .unwrap();
+    assert_eq!(manager.active_tenants().count(), 2);
+
+    let mut evicted = manager.evict_idle();
+    evicted.sort();
+    assert_eq!(evicted, [1, 2]);
+    assert_eq!(manager.active_tenants().count(), 0);
+
+    // 追い出した後も、辞書は次に使うときに適用される。
+    assert_eq!(
+        manager
+            .create_audio_query(&1, dummy_word, style_id)
+            .unwrap()
+            .kana,
+        with_dict
+    );
+}
+
+#[test]
+fn test_load_from_memory() {
+    // 一時ファイルは`voicevox_core_rs_{pid}_`で始まる名前で書き出される。
+    let prefix = format!("voicevox_core_rs_{}_", std::process::id());
+    let staged_count = || {
+        std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().starts_with(&prefix)
+            })
+            .count()
+    };
+
+    let dict_dir = test_resources::get_dict_path();
+    let dict_files: Vec<(String, Vec<u8>)> = std::fs::read_dir(&dict_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .map(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap().to_string();
+            (name, std::fs::read(path).unwrap())
+        })
+        .collect();
+    let dict_files: Vec<(&str, &[u8])> = dict_files
+        .iter()
+        .map(|(name, content)| (name.as_str(), content.as_slice()))
+        .collect();
+
+    {
+        let open_jtalk = vv::OpenJtalkRc::from_files(&dict_files).unwrap();
+        let synthesizer = vv::Synthesizer::new(&open_jtalk, Default::default()).unwrap();
+
+        let from_path = vv::VoiceModel::from_path(test_resources::get_vvm_path()).unwrap();
+        let bytes = std::fs::read(test_resources::get_vvm_path()).unwrap();
+        let from_bytes = vv::VoiceModel::from_bytes(&bytes).unwrap();
+        let from_reader = vv::VoiceModel::from_reader(
+            std::fs::File::open(test_resources::get_vvm_path()).unwrap(),
+        )
+        .unwrap();
+        let style_ids = |model: &vv::VoiceModel| -> Vec<vv::StyleId> {
+            model
+                .metas()
+                .iter()
+                .flat_map(|meta| meta.styles().iter().map(|style| style.id()))
+                .collect()
+        };
+        assert_eq!(style_ids(&from_bytes), style_ids(&from_path));
+        assert_eq!(style_ids(&from_reader), style_ids(&from_path));
+        assert_eq!(staged_count(), 3);
+
+        synthesizer.load_voice_model(&from_bytes).unwrap();
+        let style_id = from_bytes.metas()[0].styles()[0].id();
+        // 辞書の一時ディレクトリが残っているので、ユーザー辞書を適用しても辞書を読みなおせる。
+        open_jtalk
+            .use_user_dict(&vv::UserDict::new().unwrap())
+            .unwrap();
+        synthesizer
+            .tts("こんにちは", style_id, Default::default())
+            .unwrap();
+    }
+    assert_eq!(staged_count(), 0);
+
+    assert!(matches!(
+        vv::VoiceModel::from_bytes(b"not a vvm"),
+        Err(vv::AssetLoadError::Voicevox(_))
+    ));
+    assert_eq!(staged_count(), 0);
+}
+
+#[test]
+fn test_model_registry() {
+    let dir = std::env::temp_dir().join(format!("voicevox_model_registry_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(test_resources::get_vvm_path(), dir.join("0.vvm")).unwrap();
+    std::fs::write(dir.join("readme.txt"), "not a model").unwrap();
+    let cache_path = dir.join("cache.json");
+
+    let mut registry = vv::ModelRegistry::scan_with_cache(&dir, &cache_path).unwrap();
+    assert_eq!(registry.entries().len(), 1);
+    assert!(cache_path.exists());
+    let style_id = registry.metas()[0].styles()[0].id();
+    assert_eq!(
+        registry.entry_for_style(style_id).unwrap().path,
+        dir.join("0.vvm")
+    );
+
+    // 更新されていないVVMファイルのメタ情報はキャッシュから読む。
+    let cache = std::fs::read_to_string(&cache_path).unwrap();
+    let speaker_name = registry.metas()[0].name().to_string();
+    std::fs::write(
+        &cache_path,
+        cache.replace(
+            &format!("\"name\":\"{speaker_name}\""),
+            "\"name\":\"キャッシュ\"",
+        ),
+    )
+    .unwrap();
+    let cached = vv::ModelRegistry::scan_with_cache(&dir, &cache_path).unwrap();
+    assert_eq!(cached.metas()[0].name(), "キャッシュ");
+
+    // キャッシュを書き込めなくても、音声モデルは読める。
+    let unwritable = dir.join("missing").join("cache.json");
+    let uncached = vv::ModelRegistry::scan_with_cache(&dir, &unwritable).unwrap();
+    assert_eq!(uncached.entries().len(), 1);
+    assert!(!unwritable.exists());
+
+    // スタイルを使うまでは音声モデルを読み込まない。
+    let open_jtalk = vv::OpenJtalkRc::new(test_resources::get_dict_path()).unwrap();
+    let synthesizer = vv::Synthesizer::new(&open_jtalk, Default::default()).unwrap();
+    assert!(!registry.is_style_loaded(&synthesizer, style_id));
+    assert!(synthesizer.get_metas().unwrap().is_empty());
+    registry.load_style(&synthesizer, style_id).unwrap();
+    assert!(registry.is_style_loaded(&synthesizer, style_id));
+    registry.load_style(&synthesizer, style_id).unwrap();
+    synthesizer
+        .tts("こんにちは", style_id, Default::default())
+        .unwrap();
+
+    assert!(matches!(
+        registry.load_style(&synthesizer, u32::MAX),
+        Err(vv::ModelRegistryError::UnknownStyleId(u32::MAX))
+    ));
+
+    // 同じスタイルIDを持つ音声モデルがあればエラーにする。
+    std::fs::copy(test_resources::get_vvm_path(), dir.join("1.vvm")).unwrap();
+    assert!(matches!(
+        vv::ModelRegistry::scan(&dir),
+        Err(vv::ModelRegistryError::DuplicateStyleId { .. })
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
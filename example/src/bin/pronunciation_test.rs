@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use voicevox_core_rs as vv;
+
+/// テキストと期待するカナの組を並べたファイルで、読みが変わっていないかを確かめる。
+///
+/// 一致しないケースがあれば終了コード1で終了する。
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// テキストと期待するカナの組を並べたYAMLかTOMLのファイルへのパス。
+    suite: PathBuf,
+
+    /// vvmファイルへのパス。
+    #[clap(short, long)]
+    vvm: PathBuf,
+
+    /// Open JTalkの辞書ディレクトリへのパス。
+    #[clap(short, long)]
+    dict: PathBuf,
+
+    /// ユーザー辞書のJSONファイルへのパス。複数指定できる。
+    #[clap(short, long)]
+    user_dict: Vec<PathBuf>,
+
+    /// 話者ID。
+    #[clap(short, long, default_value = "0")]
+    speaker_id: u32,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let suite = vv::PronunciationSuite::load(&args.suite).unwrap();
+
+    let open_jtalk = vv::OpenJtalkRc::new(&args.dict).unwrap();
+    if !args.user_dict.is_empty() {
+        let user_dict = vv::UserDict::new().unwrap();
+        for path in &args.user_dict {
+            let other = vv::UserDict::new().unwrap();
+            other.load(path).unwrap();
+            user_dict.import(&other).unwrap();
+        }
+        open_jtalk.use_user_dict(&user_dict).unwrap();
+    }
+
+    let synthesizer = vv::Synthesizer::new(&open_jtalk, Default::default()).unwrap();
+    let voice_model = vv::VoiceModel::from_path(&args.vvm).unwrap();
+    synthesizer.load_voice_model(&voice_model).unwrap();
+
+    let report = suite.run(&synthesizer, args.speaker_id).unwrap();
+    println!("{report}");
+    if !report.is_success() {
+        std::process::exit(1);
+    }
+}